@@ -0,0 +1,81 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use criterion::*;
+
+use fastcrypto::traits::KeyPair;
+
+use std::sync::Arc;
+use sui_core::test_utils::MultiStakeAggregator;
+use sui_types::base_types::ExecutionDigests;
+use sui_types::committee::Committee;
+use sui_types::crypto::AuthoritySignInfo;
+use sui_types::gas::GasCostSummary;
+use sui_types::message_envelope::Message;
+use sui_types::messages_checkpoint::{
+    CheckpointContents, CheckpointDigest, CheckpointSummary, SignedCheckpointSummary,
+};
+
+fn checkpoint_signature_aggregation_bench(c: &mut Criterion) {
+    let (committee, key_pairs) = Committee::new_simple_test_committee_of_size(200);
+    let committee = Arc::new(committee);
+    let contents = CheckpointContents::new_with_digests_only_for_tests(
+        (0..1000).map(|_| ExecutionDigests::random()),
+    );
+    let summary = CheckpointSummary::new(
+        0,
+        1,
+        1000,
+        &contents,
+        None,
+        GasCostSummary::default(),
+        None,
+        0,
+    );
+    let digest = summary.digest();
+    // Stay below the quorum threshold, so that the one-off verification of the aggregated
+    // signature does not dominate the measurement.
+    let signatures: Vec<AuthoritySignInfo> = key_pairs
+        .iter()
+        .take(120)
+        .map(|key_pair| {
+            SignedCheckpointSummary::new(0, summary.clone(), key_pair, key_pair.public().into())
+                .into_sig()
+        })
+        .collect();
+
+    let mut group = c.benchmark_group("checkpoint_signature_aggregation");
+    group.throughput(Throughput::Elements(signatures.len() as u64));
+
+    group.bench_function("insert envelope", |b| {
+        b.iter(|| {
+            let mut aggregator =
+                MultiStakeAggregator::<CheckpointDigest, CheckpointSummary, true>::new(
+                    committee.clone(),
+                );
+            for signature in &signatures {
+                let envelope = SignedCheckpointSummary::new_from_data_and_sig(
+                    summary.clone(),
+                    signature.clone(),
+                );
+                let _ = aggregator.insert(digest, envelope);
+            }
+        })
+    });
+
+    group.bench_function("insert signature", |b| {
+        b.iter(|| {
+            let mut aggregator =
+                MultiStakeAggregator::<CheckpointDigest, CheckpointSummary, true>::new(
+                    committee.clone(),
+                );
+            for signature in &signatures {
+                let _ = aggregator.insert_signature(digest, &summary, signature.clone());
+            }
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, checkpoint_signature_aggregation_bench);
+criterion_main!(benches);
@@ -2899,6 +2899,28 @@ impl AuthorityPerEpochStore {
             .insert(&(checkpoint_seq, index), info)?)
     }
 
+    pub fn insert_checkpoint_signatures<'a>(
+        &self,
+        signatures: impl IntoIterator<
+            Item = (
+                CheckpointSequenceNumber,
+                u64,
+                &'a CheckpointSignatureMessage,
+            ),
+        >,
+    ) -> SuiResult<()> {
+        let tables = self.tables()?;
+        let mut batch = tables.pending_checkpoint_signatures.batch();
+        batch.insert_batch(
+            &tables.pending_checkpoint_signatures,
+            signatures
+                .into_iter()
+                .map(|(checkpoint_seq, index, info)| ((checkpoint_seq, index), info)),
+        )?;
+        batch.write()?;
+        Ok(())
+    }
+
     pub(crate) fn record_epoch_pending_certs_process_time_metric(&self) {
         if let Some(epoch_close_time) = *self.epoch_close_time.read() {
             self.metrics
@@ -2921,7 +2943,7 @@ impl AuthorityPerEpochStore {
                 .epoch_last_checkpoint_created_time_since_epoch_close_ms
                 .set(epoch_close_time.elapsed().as_millis() as i64);
         }
-        info!(epoch=?self.epoch(), "Epoch statistics: checkpoint_count={:?}, transaction_count={:?}, total_gas_reward={:?}", stats.checkpoint_count, stats.transaction_count, stats.total_gas_reward);
+        info!(epoch=?self.epoch(), "Epoch statistics: checkpoint_count={:?}, transaction_count={:?}, total_gas_reward={:?}, total_storage_cost={:?}, total_storage_rebate={:?}, total_non_refundable_storage_fee={:?}", stats.checkpoint_count, stats.transaction_count, stats.total_gas_reward, stats.total_storage_cost, stats.total_storage_rebate, stats.total_non_refundable_storage_fee);
         self.metrics
             .epoch_checkpoint_count
             .set(stats.checkpoint_count as i64);
@@ -2931,6 +2953,15 @@ impl AuthorityPerEpochStore {
         self.metrics
             .epoch_total_gas_reward
             .set(stats.total_gas_reward as i64);
+        self.metrics
+            .epoch_total_storage_cost
+            .set(stats.total_storage_cost as i64);
+        self.metrics
+            .epoch_total_storage_rebate
+            .set(stats.total_storage_rebate as i64);
+        self.metrics
+            .epoch_total_non_refundable_storage_fee
+            .set(stats.total_non_refundable_storage_fee as i64);
     }
 
     pub fn record_epoch_reconfig_start_time_metric(&self) {
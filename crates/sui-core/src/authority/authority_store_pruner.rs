@@ -268,6 +268,19 @@ impl AuthorityStorePruner {
 
         let mut checkpoints_batch = checkpoint_db.certified_checkpoints.batch();
 
+        // The checkpoints to prune are the ones directly below and including `checkpoint_number`.
+        let pruned_sequence_numbers = (checkpoint_number + 1)
+            .saturating_sub(checkpoints_to_prune.len() as u64)
+            ..=checkpoint_number;
+        checkpoints_batch.delete_batch(
+            &checkpoint_db.checkpoint_provenance,
+            pruned_sequence_numbers.clone(),
+        )?;
+        checkpoints_batch.delete_batch(
+            &checkpoint_db.checkpoint_timestamps,
+            pruned_sequence_numbers,
+        )?;
+
         let checkpoint_content_digests =
             checkpoint_content_to_prune.iter().map(|ckpt| ckpt.digest());
         checkpoints_batch.delete_batch(
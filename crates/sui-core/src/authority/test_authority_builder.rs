@@ -233,6 +233,9 @@ impl<'a> TestAuthorityBuilder<'a> {
                 genesis.checkpoint_contents().clone(),
                 &epoch_store,
             );
+            checkpoint_store
+                .insert_genesis_committee(&genesis_committee)
+                .unwrap();
         }
         let index_store = if self.disable_indexer {
             None
@@ -210,6 +210,9 @@ impl InsertState {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use rand::rngs::StdRng;
+    use rand::seq::SliceRandom;
+    use rand::{Rng, SeedableRng};
     use sui_types::base_types::ObjectDigest;
     use sui_types::base_types::{ObjectID, SequenceNumber};
     use sui_types::effects::TransactionEffects;
@@ -266,6 +269,47 @@ mod tests {
         assert!(r.contains(&2));
     }
 
+    #[test]
+    pub fn test_causal_order_respects_dependencies() {
+        let mut rng = StdRng::seed_from_u64(0);
+        for _ in 0..100 {
+            let count = rng.gen_range(1..100u8);
+            let mut effects: Vec<_> = (1..=count)
+                .map(|i| {
+                    let mut dependencies: Vec<_> =
+                        (1..i).filter(|_| rng.gen_bool(0.2)).map(d).collect();
+                    if rng.gen_bool(0.1) {
+                        // Dependency outside of the checkpoint
+                        dependencies.push(d(200));
+                    }
+                    e(d(i), dependencies)
+                })
+                .collect();
+            effects.shuffle(&mut rng);
+
+            let sorted = CausalOrder::causal_sort(effects.clone());
+            assert_eq!(sorted.len(), effects.len());
+            let positions: HashMap<_, _> = sorted
+                .iter()
+                .enumerate()
+                .map(|(position, effects)| (*effects.transaction_digest(), position))
+                .collect();
+            for effects in &sorted {
+                let position = positions[effects.transaction_digest()];
+                for dependency in effects.dependencies() {
+                    if let Some(dependency_position) = positions.get(dependency) {
+                        assert!(
+                            *dependency_position < position,
+                            "{:?} is ordered before its dependency {:?}",
+                            effects.transaction_digest(),
+                            dependency
+                        );
+                    }
+                }
+            }
+        }
+    }
+
     fn extract(e: Vec<TransactionEffects>) -> Vec<u8> {
         e.into_iter()
             .map(|e| e.transaction_digest().inner()[0])
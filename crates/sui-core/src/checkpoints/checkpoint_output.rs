@@ -33,6 +33,19 @@ pub trait CheckpointOutput: Sync + Send + 'static {
 pub trait CertifiedCheckpointOutput: Sync + Send + 'static {
     async fn certified_checkpoint_created(&self, summary: &CertifiedCheckpointSummary)
         -> SuiResult;
+
+    /// Called with all the checkpoints certified in one aggregation round, in order. Outputs
+    /// that benefit from batching can override it, by default each checkpoint is passed to
+    /// `certified_checkpoint_created` in turn.
+    async fn certified_checkpoints_created(
+        &self,
+        summaries: &[CertifiedCheckpointSummary],
+    ) -> SuiResult {
+        for summary in summaries {
+            self.certified_checkpoint_created(summary).await?;
+        }
+        Ok(())
+    }
 }
 
 pub struct SubmitCheckpointToConsensus<T> {
@@ -3,15 +3,25 @@
 
 use mysten_metrics::histogram::Histogram;
 use prometheus::{
+    register_histogram_vec_with_registry, register_histogram_with_registry,
     register_int_counter_vec_with_registry, register_int_counter_with_registry,
-    register_int_gauge_vec_with_registry, register_int_gauge_with_registry, IntCounter,
-    IntCounterVec, IntGauge, IntGaugeVec, Registry,
+    register_int_gauge_vec_with_registry, register_int_gauge_with_registry, HistogramVec,
+    IntCounter, IntCounterVec, IntGauge, IntGaugeVec, Registry,
 };
 use std::sync::Arc;
 
+const BUILD_DURATION_SEC_BUCKETS: &[f64] = &[
+    0.001, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1., 2.5, 5., 10., 30., 60.,
+];
+
+const CERTIFICATION_LATENCY_SEC_BUCKETS: &[f64] = &[
+    0.05, 0.1, 0.25, 0.5, 0.75, 1., 1.5, 2., 3., 5., 10., 30., 60.,
+];
+
 pub struct CheckpointMetrics {
     pub last_certified_checkpoint: IntGauge,
     pub last_constructed_checkpoint: IntGauge,
+    pub builder_last_commit_height: IntGauge,
     pub checkpoint_errors: IntCounter,
     pub transactions_included_in_checkpoint: IntCounter,
     pub checkpoint_roots_count: IntCounter,
@@ -20,10 +30,19 @@ pub struct CheckpointMetrics {
     pub last_sent_checkpoint_signature: IntGauge,
     pub highest_accumulated_epoch: IntGauge,
     pub checkpoint_creation_latency_ms: Histogram,
+    pub checkpoint_build_duration_seconds: HistogramVec,
+    pub checkpoint_certification_latency_seconds: prometheus::Histogram,
     pub remote_checkpoint_forks: IntCounter,
     pub split_brain_checkpoint_forks: IntCounter,
+    pub rejected_checkpoint_signatures: IntCounter,
+    pub dead_lettered_checkpoint_outputs: IntCounter,
+    pub highest_pruned_checkpoint: IntGauge,
+    pub checkpoint_pruning_lag: IntGauge,
+    pub checkpoint_future_epoch_effects: IntCounter,
     pub last_created_checkpoint_age_ms: Histogram,
     pub last_certified_checkpoint_age_ms: Histogram,
+    pub chunks_per_commit: Histogram,
+    pub checkpoint_content_bytes: Histogram,
 }
 
 impl CheckpointMetrics {
@@ -41,6 +60,12 @@ impl CheckpointMetrics {
                 registry
             )
             .unwrap(),
+            builder_last_commit_height: register_int_gauge_with_registry!(
+                "builder_last_commit_height",
+                "Consensus commit height of the last pending checkpoint processed by the checkpoint builder",
+                registry
+            )
+            .unwrap(),
             last_created_checkpoint_age_ms: Histogram::new_in_registry(
                 "last_created_checkpoint_age_ms",
                 "Age of the last created checkpoint",
@@ -51,6 +76,16 @@ impl CheckpointMetrics {
                 "Age of the last certified checkpoint",
                 registry
             ),
+            chunks_per_commit: Histogram::new_in_registry(
+                "chunks_per_commit",
+                "Number of checkpoints each pending checkpoint from consensus was split into",
+                registry
+            ),
+            checkpoint_content_bytes: Histogram::new_in_registry(
+                "checkpoint_content_bytes",
+                "Serialized size in bytes of the contents of each constructed checkpoint",
+                registry
+            ),
             checkpoint_errors: register_int_counter_with_registry!(
                 "checkpoint_errors",
                 "Checkpoints errors count",
@@ -100,6 +135,21 @@ impl CheckpointMetrics {
                 "Latency from consensus commit timstamp to local checkpoint creation in milliseconds",
                 registry,
             ),
+            checkpoint_build_duration_seconds: register_histogram_vec_with_registry!(
+                "checkpoint_build_duration_seconds",
+                "Time spent by the checkpoint builder to make checkpoints from a pending checkpoint, by phase",
+                &["phase"],
+                BUILD_DURATION_SEC_BUCKETS.to_vec(),
+                registry,
+            )
+            .unwrap(),
+            checkpoint_certification_latency_seconds: register_histogram_with_registry!(
+                "checkpoint_certification_latency_seconds",
+                "Time from the start of signature aggregation for a checkpoint until a quorum of signatures is reached",
+                CERTIFICATION_LATENCY_SEC_BUCKETS.to_vec(),
+                registry,
+            )
+            .unwrap(),
             remote_checkpoint_forks: register_int_counter_with_registry!(
                 "remote_checkpoint_forks",
                 "Number of remote checkpoints that forked from local checkpoints",
@@ -112,6 +162,36 @@ impl CheckpointMetrics {
                 registry
             )
             .unwrap(),
+            rejected_checkpoint_signatures: register_int_counter_with_registry!(
+                "rejected_checkpoint_signatures",
+                "Number of invalid checkpoint signatures rejected by the aggregator, not indicating a fork",
+                registry
+            )
+            .unwrap(),
+            dead_lettered_checkpoint_outputs: register_int_counter_with_registry!(
+                "dead_lettered_checkpoint_outputs",
+                "Number of checkpoints persisted despite a failed output, queued for output retry",
+                registry
+            )
+            .unwrap(),
+            highest_pruned_checkpoint: register_int_gauge_with_registry!(
+                "highest_pruned_checkpoint",
+                "Highest checkpoint pruned from the checkpoint store",
+                registry
+            )
+            .unwrap(),
+            checkpoint_pruning_lag: register_int_gauge_with_registry!(
+                "checkpoint_pruning_lag",
+                "Number of checkpoints between the highest executed and the highest pruned checkpoint",
+                registry
+            )
+            .unwrap(),
+            checkpoint_future_epoch_effects: register_int_counter_with_registry!(
+                "checkpoint_future_epoch_effects",
+                "Number of effects found while building checkpoints that claim to be executed in a future epoch",
+                registry
+            )
+            .unwrap(),
         };
         Arc::new(this)
     }
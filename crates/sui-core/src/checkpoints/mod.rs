@@ -16,12 +16,15 @@ pub use crate::checkpoints::checkpoint_output::{
 pub use crate::checkpoints::metrics::CheckpointMetrics;
 use crate::stake_aggregator::{InsertResult, MultiStakeAggregator};
 use crate::state_accumulator::StateAccumulator;
-use diffy::create_patch;
+use diffy::{create_patch, Patch};
+use fastcrypto::hash::HashFunction;
 use futures::future::{select, Either};
+use futures::stream::{self, Stream};
 use futures::FutureExt;
 use itertools::Itertools;
 use mysten_metrics::{monitored_scope, spawn_monitored_task, MonitoredFutureExt};
 use parking_lot::Mutex;
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use sui_macros::fail_point;
 use sui_network::default_mysten_network_config;
@@ -29,55 +32,204 @@ use sui_types::base_types::ConciseableName;
 
 use crate::authority::authority_per_epoch_store::AuthorityPerEpochStore;
 use crate::consensus_handler::SequencedConsensusTransactionKey;
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use rand::rngs::OsRng;
 use rand::seq::SliceRandom;
+use rand::Rng;
 use std::collections::BTreeMap;
 use std::collections::{HashMap, HashSet};
 use std::fs::File;
-use std::io::Write;
-use std::path::Path;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use sui_protocol_config::ProtocolVersion;
 use sui_types::base_types::{AuthorityName, EpochId, TransactionDigest};
-use sui_types::committee::StakeUnit;
-use sui_types::crypto::AuthorityStrongQuorumSignInfo;
+use sui_types::committee::{Committee, CommitteeTrait, StakeUnit};
+use sui_types::crypto::{AuthorityStrongQuorumSignInfo, DefaultHash};
 use sui_types::digests::{CheckpointContentsDigest, CheckpointDigest};
 use sui_types::effects::{TransactionEffects, TransactionEffectsAPI};
-use sui_types::error::{SuiError, SuiResult};
+use sui_types::error::{SuiError, SuiResult, UserInputError};
 use sui_types::gas::GasCostSummary;
 use sui_types::message_envelope::Message;
+use sui_types::messages_checkpoint::CheckpointRequestV2;
 use sui_types::messages_checkpoint::{
     CertifiedCheckpointSummary, CheckpointContents, CheckpointResponseV2, CheckpointSequenceNumber,
     CheckpointSignatureMessage, CheckpointSummary, CheckpointSummaryResponse, CheckpointTimestamp,
     EndOfEpochData, FullCheckpointContents, TrustedCheckpoint, VerifiedCheckpoint,
     VerifiedCheckpointContents,
 };
-use sui_types::messages_checkpoint::{CheckpointRequestV2, SignedCheckpointSummary};
 use sui_types::messages_consensus::ConsensusTransactionKey;
 use sui_types::signature::GenericSignature;
 use sui_types::sui_system_state::{SuiSystemState, SuiSystemStateTrait};
 use sui_types::transaction::{TransactionDataAPI, TransactionKind};
 use tokio::{
-    sync::{watch, Notify},
+    sync::{broadcast, watch, Notify},
     time::timeout,
 };
 use tracing::{debug, error, info, instrument, warn};
 use typed_store::traits::{TableSummary, TypedStoreDebug};
 use typed_store::Map;
 use typed_store::{
-    rocks::{DBMap, MetricConf},
+    rocks::{DBMap, DBOptions, MetricConf},
     TypedStoreError,
 };
 use typed_store_derive::DBMapUtils;
 
 pub type CheckpointCommitHeight = u64;
 
+/// Number of most recent checkpoint build failures kept in the `build_failures` table.
+pub const MAX_RECORDED_BUILD_FAILURES: u64 = 100;
+/// Number of `CertifiedCheckpointEvent`s buffered for each subscriber before the oldest ones are
+/// dropped for subscribers that are behind.
+pub const CERTIFIED_EVENTS_CHANNEL_CAPACITY: usize = 1000;
+/// Directory of the system temporary directory where split brain fork dumps are written.
+const FORK_DUMPS_DIR: &str = "checkpoint_fork_dumps";
+const FORK_DUMP_FILE_PREFIX: &str = "checkpoint_fork_dump_";
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct EpochStats {
     pub checkpoint_count: u64,
     pub transaction_count: u64,
     pub total_gas_reward: u64,
+    pub total_storage_cost: u64,
+    pub total_storage_rebate: u64,
+    pub total_non_refundable_storage_fee: u64,
+}
+
+/// Emitted by the checkpoint aggregator for every checkpoint it certifies, see
+/// `CheckpointService::subscribe_certified_events`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CertifiedCheckpointEvent {
+    pub seq: CheckpointSequenceNumber,
+    pub digest: CheckpointDigest,
+    pub epoch: EpochId,
+    pub timestamp_ms: CheckpointTimestamp,
+    pub num_txns: u64,
+}
+
+/// Compact projection of a certified checkpoint, carrying only what is needed to follow the
+/// chain of checkpoints and the committee transitions.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CheckpointHeader {
+    pub sequence_number: CheckpointSequenceNumber,
+    pub epoch: EpochId,
+    pub digest: CheckpointDigest,
+    pub previous_digest: Option<CheckpointDigest>,
+    pub content_digest: CheckpointContentsDigest,
+    pub timestamp_ms: CheckpointTimestamp,
+    /// Committee of the next epoch, only set for the last checkpoint of an epoch
+    pub next_epoch_committee: Option<Vec<(AuthorityName, StakeUnit)>>,
+}
+
+impl From<&VerifiedCheckpoint> for CheckpointHeader {
+    fn from(checkpoint: &VerifiedCheckpoint) -> Self {
+        Self {
+            sequence_number: checkpoint.sequence_number,
+            epoch: checkpoint.epoch,
+            digest: *checkpoint.digest(),
+            previous_digest: checkpoint.previous_digest,
+            content_digest: checkpoint.content_digest,
+            timestamp_ms: checkpoint.timestamp_ms,
+            next_epoch_committee: checkpoint.next_epoch_committee().map(|c| c.to_vec()),
+        }
+    }
+}
+
+/// A failed attempt of the checkpoint builder at building the checkpoints of a commit.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BuildFailure {
+    pub commit_height: CheckpointCommitHeight,
+    pub error: String,
+    /// Wall clock time of the failure, in milliseconds since the unix epoch
+    pub timestamp_ms: u64,
+}
+
+/// How far the checkpoint builder got through the pending checkpoints written by consensus.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BuilderStatus {
+    /// Commit height of the last pending checkpoint built, if any was built in this epoch
+    pub last_built_height: Option<CheckpointCommitHeight>,
+    /// Sequence number of the last checkpoint built, if any was built in this epoch
+    pub last_built_seq: Option<CheckpointSequenceNumber>,
+    /// Number of pending checkpoints written by consensus that are not built yet
+    pub pending_commits: usize,
+}
+
+/// How a certified checkpoint was first obtained by this node.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CheckpointProvenance {
+    /// Certified by the local checkpoint aggregator from the signatures of the committee.
+    LocalAggregation,
+    /// Received already certified through state sync, from the given peer if it is known.
+    StateSync { from: Option<AuthorityName> },
+}
+
+/// Standing of a checkpoint digest in the local store.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum CheckpointStatus {
+    /// The digest is the one of a certified checkpoint.
+    Certified,
+    /// The digest is the one of a checkpoint built locally that is not certified (yet).
+    LocallyBuiltOnly,
+    Unknown,
+}
+
+/// Breakdown of the signatures received for a checkpoint on which quorum became unreachable.
+#[derive(Clone, Debug)]
+pub struct SplitBrainReport {
+    pub checkpoint_seq: CheckpointSequenceNumber,
+    pub local_digest: CheckpointDigest,
+    /// Each checkpoint digest signed by some validators, with the signers and their total
+    /// stake, ordered by decreasing stake
+    pub factions: Vec<(CheckpointDigest, Vec<AuthorityName>, StakeUnit)>,
+}
+
+/// Number of checkpoints certified by the aggregator during its epoch, and for how many of
+/// them the signature of each validator was aggregated.
+#[derive(Default)]
+struct EpochParticipation {
+    certified_checkpoints: u64,
+    signed_checkpoints: HashMap<AuthorityName, u64>,
+}
+
+impl EpochParticipation {
+    fn record(&mut self, signers: &HashSet<AuthorityName>) {
+        self.certified_checkpoints += 1;
+        for signer in signers {
+            *self.signed_checkpoints.entry(*signer).or_default() += 1;
+        }
+    }
+
+    /// Returns, for each member of `committee`, the fraction of the certified checkpoints for
+    /// which its signature was aggregated.
+    fn fractions(&self, committee: &Committee) -> HashMap<AuthorityName, f64> {
+        committee
+            .names()
+            .map(|name| {
+                let signed = self
+                    .signed_checkpoints
+                    .get(name)
+                    .copied()
+                    .unwrap_or_default();
+                let fraction = if self.certified_checkpoints == 0 {
+                    0.0
+                } else {
+                    signed as f64 / self.certified_checkpoints as f64
+                };
+                (*name, fraction)
+            })
+            .collect()
+    }
+}
+
+/// The signers of a certified checkpoint, resolved against the committee of its epoch.
+pub struct CertificationInfo {
+    pub signers: Vec<AuthorityName>,
+    pub signing_stake: StakeUnit,
+    /// Fraction of the total committee stake that signed the checkpoint
+    pub signing_stake_fraction: f64,
+    pub total_stake: StakeUnit,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -101,6 +253,209 @@ pub struct BuilderCheckpointSummary {
     pub position_in_commit: usize,
 }
 
+/// Determines how the checkpoint builder locates the last checkpoint of the previous epoch
+/// when its builder DB does not contain any checkpoints yet.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum PreviousEpochCheckpointLookup {
+    /// The last checkpoint of the previous epoch must be recorded in the epoch boundary map,
+    /// otherwise the builder panics. This is the expected behavior for normal operation.
+    #[default]
+    Panic,
+    /// The node may have been started mid-chain from a snapshot, in which case the epoch
+    /// boundary map can be missing the previous epoch. Fall back to scanning the certified
+    /// checkpoints for the boundary, and retry later instead of panicking if it is not found.
+    SnapshotRecovery,
+}
+
+/// What `CheckpointStore::update_highest_pruned_checkpoint` does when asked to move the pruned
+/// watermark past the highest executed checkpoint.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum PrunedWatermarkViolation {
+    /// Return an error and leave the watermark untouched.
+    #[default]
+    Error,
+    /// Log an error and update the watermark anyway.
+    Warn,
+}
+
+/// Callback invoked by the checkpoint aggregator when it detects a split brain, i.e. when quorum
+/// can no longer be reached on the checkpoint being certified. Operators can plug in a halt of
+/// transaction processing here to avoid building on top of forked output. The default hook
+/// does nothing.
+#[derive(Clone)]
+pub struct SplitBrainHaltHook(Arc<dyn Fn() + Send + Sync>);
+
+impl SplitBrainHaltHook {
+    pub fn new(hook: impl Fn() + Send + Sync + 'static) -> Self {
+        Self(Arc::new(hook))
+    }
+
+    fn halt(&self) {
+        (self.0)()
+    }
+}
+
+impl Default for SplitBrainHaltHook {
+    fn default() -> Self {
+        Self::new(|| ())
+    }
+}
+
+impl std::fmt::Debug for SplitBrainHaltHook {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("SplitBrainHaltHook")
+    }
+}
+
+/// Signal checked by the checkpoint builder before building a checkpoint that does not close the
+/// epoch. While the watched value is true, the build is deferred until it turns false, so
+/// operators can throttle checkpoint building on memory constrained nodes. Dropping the sender
+/// releases the pressure. The default signal never reports pressure.
+#[derive(Clone, Debug)]
+pub struct MemoryPressureSignal(watch::Receiver<bool>);
+
+impl MemoryPressureSignal {
+    pub fn new(under_pressure: watch::Receiver<bool>) -> Self {
+        Self(under_pressure)
+    }
+
+    fn under_pressure(&self) -> bool {
+        *self.0.borrow()
+    }
+
+    /// Waits until no pressure is reported, or the sender is dropped.
+    async fn released(&mut self) {
+        let _ = self.0.wait_for(|under_pressure| !under_pressure).await;
+    }
+}
+
+impl Default for MemoryPressureSignal {
+    fn default() -> Self {
+        Self::new(watch::channel(false).1)
+    }
+}
+
+/// Callback invoked by the checkpoint builder for every checkpoint it constructs, right after
+/// the checkpoint output, for in-process consumers that don't need the async `CheckpointOutput`
+/// trait. The callback runs on the builder task and delays checkpoint building, so it must be
+/// fast and must not block. The default hook does nothing.
+#[derive(Clone)]
+pub struct CheckpointConstructedHook(
+    Arc<dyn Fn(&CheckpointSummary, &CheckpointContents) + Send + Sync>,
+);
+
+impl CheckpointConstructedHook {
+    pub fn new(
+        hook: impl Fn(&CheckpointSummary, &CheckpointContents) + Send + Sync + 'static,
+    ) -> Self {
+        Self(Arc::new(hook))
+    }
+
+    fn checkpoint_constructed(&self, summary: &CheckpointSummary, contents: &CheckpointContents) {
+        (self.0)(summary, contents)
+    }
+}
+
+impl Default for CheckpointConstructedHook {
+    fn default() -> Self {
+        Self::new(|_, _| ())
+    }
+}
+
+impl std::fmt::Debug for CheckpointConstructedHook {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("CheckpointConstructedHook")
+    }
+}
+
+/// Tunables for the checkpoint builder and aggregator spawned by `CheckpointService`.
+#[derive(Clone, Debug)]
+pub struct CheckpointServiceConfig {
+    pub previous_epoch_checkpoint_lookup: PreviousEpochCheckpointLookup,
+    /// How long the aggregator waits for a notification before re-scanning pending signatures.
+    pub aggregation_poll_interval: Duration,
+    /// When set, a checkpoint whose output fails is recorded in the `failed_outputs` table and
+    /// persisted anyway, and the output is retried later by the builder. Otherwise the failure
+    /// aborts the write and the whole checkpoint is rebuilt.
+    pub dead_letter_failed_outputs: bool,
+    /// Maximum random delay added to the 1s sleep after a builder or aggregator error, as a
+    /// percentage of that sleep, so that validators hitting the same error don't retry in sync.
+    pub error_retry_jitter_percent: u64,
+    /// Number of dependency levels the builder may have to walk from the roots of a pending
+    /// checkpoint before it warns about a deep dependency chain.
+    pub deep_dependency_chain_warn_depth: usize,
+    /// Invoked by the aggregator right after a split brain is detected.
+    pub split_brain_halt_hook: SplitBrainHaltHook,
+    /// Number of checkpoints preceding a forked checkpoint whose local and peer digests are
+    /// included in the fork dump, to tell whether the fork started earlier.
+    pub split_brain_lookback: u64,
+    /// Number of validators queried for each digest that differs from the local one when a
+    /// split brain is diagnosed, so that diagnostics still cover a faction if some of its
+    /// validators are unreachable.
+    pub split_brain_peers_per_faction: usize,
+    /// Number of split brain fork dumps kept in the fork dump directory of the system temporary
+    /// directory. The oldest dumps are deleted when a new one is written.
+    pub max_retained_fork_dumps: usize,
+    /// When set, the builder persists the `EpochStats` computed for the last checkpoint of each
+    /// epoch in the `epoch_stats` table.
+    pub persist_epoch_stats: bool,
+    /// When set, the builder records its last failures in the `build_failures` table, see
+    /// `CheckpointStore::recent_build_failures`.
+    pub record_build_failures: bool,
+    /// When set, the builder fails to build a checkpoint whose timestamp is lower than the one of
+    /// the previous checkpoint, and retries later, instead of only logging an error.
+    pub reject_timestamp_regression: bool,
+    /// When set, the builder checks that every transaction read for a checkpoint has the digest
+    /// of the effects it is paired with, and fails to build the checkpoint otherwise.
+    pub validate_transaction_digests: bool,
+    /// Invoked by the builder for every checkpoint it constructs.
+    pub on_checkpoint_constructed: CheckpointConstructedHook,
+    /// When set, the builder does not build a checkpoint that does not close the epoch while
+    /// this many checkpoints it built are not certified yet, so that locally computed
+    /// checkpoints don't pile up when certification stalls.
+    pub max_uncertified_checkpoints: Option<u64>,
+    /// Checked by the builder before building a checkpoint that does not close the epoch.
+    pub memory_pressure_signal: MemoryPressureSignal,
+    /// When set, the builder reads the effects of the roots of a pending checkpoint in batches
+    /// of at most this many transactions, one batch after the other, to bound the memory used
+    /// by the reads of large consensus commits.
+    pub max_effects_read_batch: Option<usize>,
+    /// When set, the aggregator stops certifying checkpoints once the next checkpoint to
+    /// certify is above this sequence number. Can be changed at runtime with
+    /// `CheckpointService::set_certification_ceiling`.
+    pub certification_ceiling: Option<CheckpointSequenceNumber>,
+    /// When set, the aggregator records in the `checkpoint_provenance` table that the checkpoints
+    /// it certifies were obtained through local aggregation, see
+    /// `CheckpointStore::get_checkpoint_provenance`.
+    pub record_checkpoint_provenance: bool,
+}
+
+impl Default for CheckpointServiceConfig {
+    fn default() -> Self {
+        Self {
+            previous_epoch_checkpoint_lookup: PreviousEpochCheckpointLookup::default(),
+            aggregation_poll_interval: Duration::from_secs(1),
+            dead_letter_failed_outputs: false,
+            error_retry_jitter_percent: 10,
+            deep_dependency_chain_warn_depth: 1000,
+            split_brain_halt_hook: SplitBrainHaltHook::default(),
+            split_brain_lookback: 5,
+            split_brain_peers_per_faction: 1,
+            max_retained_fork_dumps: 10,
+            persist_epoch_stats: false,
+            record_build_failures: false,
+            reject_timestamp_regression: false,
+            validate_transaction_digests: false,
+            on_checkpoint_constructed: CheckpointConstructedHook::default(),
+            max_uncertified_checkpoints: None,
+            memory_pressure_signal: MemoryPressureSignal::default(),
+            max_effects_read_batch: None,
+            certification_ceiling: None,
+            record_checkpoint_provenance: false,
+        }
+    }
+}
+
 #[derive(DBMapUtils)]
 pub struct CheckpointStore {
     /// Maps checkpoint contents digest to checkpoint contents
@@ -119,6 +474,10 @@ pub struct CheckpointStore {
     pub(crate) certified_checkpoints: DBMap<CheckpointSequenceNumber, TrustedCheckpoint>,
     /// Map from checkpoint digest to certified checkpoint
     pub(crate) checkpoint_by_digest: DBMap<CheckpointDigest, TrustedCheckpoint>,
+    /// Map from checkpoint sequence number to the timestamp of the certified checkpoint, so
+    /// that timestamps can be read without deserializing the whole summary. Pruned along with
+    /// the checkpoint contents.
+    pub(crate) checkpoint_timestamps: DBMap<CheckpointSequenceNumber, CheckpointTimestamp>,
 
     /// Store locally computed checkpoint summaries so that we can detect forks and log useful
     /// information. Can be pruned as soon as we verify that we are in agreement with the latest
@@ -128,21 +487,66 @@ pub struct CheckpointStore {
     /// A map from epoch ID to the sequence number of the last checkpoint in that epoch.
     epoch_last_checkpoint_map: DBMap<EpochId, CheckpointSequenceNumber>,
 
+    /// The committee of epoch 0, which unlike the committees of later epochs is not declared by
+    /// any checkpoint and is recorded from genesis instead.
+    genesis_committee: DBMap<(), Committee>,
+
     /// Watermarks used to determine the highest verified, fully synced, and
     /// fully executed checkpoints
     pub(crate) watermarks: DBMap<CheckpointWatermark, (CheckpointSequenceNumber, CheckpointDigest)>,
+
+    /// Locally built checkpoints whose output failed and was dead-lettered, pending retry by the
+    /// checkpoint builder.
+    failed_outputs: DBMap<CheckpointSequenceNumber, ()>,
+
+    /// Maps epoch ID to the statistics computed when the last checkpoint of that epoch was
+    /// built. Only populated when `persist_epoch_stats` is enabled.
+    epoch_stats: DBMap<EpochId, EpochStats>,
+
+    /// The last `MAX_RECORDED_BUILD_FAILURES` failures of the checkpoint builder, keyed by an
+    /// increasing index. Only populated when `record_build_failures` is enabled.
+    build_failures: DBMap<u64, BuildFailure>,
+
+    /// Maps checkpoint sequence number to how this node first obtained the certified checkpoint.
+    /// Only populated for the writers that are configured to record it, and pruned along with
+    /// the checkpoint contents.
+    pub(crate) checkpoint_provenance: DBMap<CheckpointSequenceNumber, CheckpointProvenance>,
 }
 
 impl CheckpointStore {
     pub fn new(path: &Path) -> Arc<Self> {
+        Self::new_with_metric_conf(path, MetricConf::new("checkpoint"))
+    }
+
+    /// Same as `new`, with the given metric configuration instead of the default `checkpoint`
+    /// one, so that processes opening several stores can tell their RocksDB metrics apart.
+    pub fn new_with_metric_conf(path: &Path, metric_conf: MetricConf) -> Arc<Self> {
         Arc::new(Self::open_tables_read_write(
             path.to_path_buf(),
-            MetricConf::new("checkpoint"),
+            metric_conf,
             None,
             None,
         ))
     }
 
+    /// Opens the store with zstd compression at the given level enabled for all LSM levels of the
+    /// `checkpoint_content` and `full_checkpoint_content` tables. RocksDB records the compression
+    /// type of every data block, so reads transparently decompress, and a store previously
+    /// written without compression can be opened with this constructor: existing data stays
+    /// readable and gets rewritten compressed as it is compacted.
+    pub fn new_with_compression(path: &Path, zstd_level: i32) -> Arc<Self> {
+        let mut config = Self::configurator();
+        config.checkpoint_content = zstd_db_options(config.checkpoint_content, zstd_level);
+        config.full_checkpoint_content =
+            zstd_db_options(config.full_checkpoint_content, zstd_level);
+        Arc::new(Self::open_tables_read_write(
+            path.to_path_buf(),
+            MetricConf::new("checkpoint"),
+            None,
+            Some(config.build()),
+        ))
+    }
+
     pub fn open_readonly(path: &Path) -> CheckpointStoreReadOnly {
         Self::get_read_only_handle(
             path.to_path_buf(),
@@ -152,22 +556,16 @@ impl CheckpointStore {
         )
     }
 
+    /// Inserts the genesis checkpoint into the store, and when the node is still in epoch 0,
+    /// also seeds the checkpoint builder with it so that the next checkpoints can be built on
+    /// top of it. Use `ingest_genesis_checkpoint` to only record genesis for history.
     pub fn insert_genesis_checkpoint(
         &self,
         checkpoint: VerifiedCheckpoint,
         contents: CheckpointContents,
         epoch_store: &AuthorityPerEpochStore,
     ) {
-        assert_eq!(
-            checkpoint.epoch(),
-            0,
-            "can't call insert_genesis_checkpoint with a checkpoint not in epoch 0"
-        );
-        assert_eq!(
-            *checkpoint.sequence_number(),
-            0,
-            "can't call insert_genesis_checkpoint with a checkpoint that doesn't have a sequence number of 0"
-        );
+        assert_genesis_checkpoint(&checkpoint);
 
         // Only insert the genesis checkpoint if the DB is empty and doesn't have it already
         if self
@@ -186,12 +584,46 @@ impl CheckpointStore {
                     "Not inserting checkpoint builder data for genesis checkpoint",
                 );
             }
-            self.insert_checkpoint_contents(contents).unwrap();
-            self.insert_verified_checkpoint(&checkpoint).unwrap();
-            self.update_highest_synced_checkpoint(&checkpoint).unwrap();
+            self.write_genesis_checkpoint(checkpoint, contents);
+        }
+    }
+
+    /// Records the committee of epoch 0, as taken from genesis, so that `committee_for_epoch` can
+    /// return it.
+    pub fn insert_genesis_committee(&self, committee: &Committee) -> Result<(), TypedStoreError> {
+        assert_eq!(committee.epoch(), 0, "genesis committee must be of epoch 0");
+        self.genesis_committee.insert(&(), committee)
+    }
+
+    /// Inserts the genesis checkpoint into the store for historical completeness, without
+    /// touching checkpoint builder data. Intended for nodes that join after epoch 0, for which
+    /// building on top of genesis makes no sense.
+    pub fn ingest_genesis_checkpoint(
+        &self,
+        checkpoint: VerifiedCheckpoint,
+        contents: CheckpointContents,
+    ) {
+        assert_genesis_checkpoint(&checkpoint);
+
+        if self
+            .get_checkpoint_by_digest(checkpoint.digest())
+            .unwrap()
+            .is_none()
+        {
+            self.write_genesis_checkpoint(checkpoint, contents);
         }
     }
 
+    fn write_genesis_checkpoint(
+        &self,
+        checkpoint: VerifiedCheckpoint,
+        contents: CheckpointContents,
+    ) {
+        self.insert_checkpoint_contents(contents).unwrap();
+        self.insert_verified_checkpoint(&checkpoint).unwrap();
+        self.update_highest_synced_checkpoint(&checkpoint).unwrap();
+    }
+
     pub fn get_checkpoint_by_digest(
         &self,
         digest: &CheckpointDigest,
@@ -210,6 +642,28 @@ impl CheckpointStore {
             .map(|maybe_checkpoint| maybe_checkpoint.map(|c| c.into()))
     }
 
+    /// Returns the certified checkpoint `sequence_number` together with its contents, or None if
+    /// either of them is not stored, e.g. because the contents were pruned in between.
+    pub fn get_checkpoint_with_contents(
+        &self,
+        sequence_number: CheckpointSequenceNumber,
+    ) -> SuiResult<Option<(VerifiedCheckpoint, CheckpointContents)>> {
+        let Some(checkpoint) = self.get_checkpoint_by_sequence_number(sequence_number)? else {
+            return Ok(None);
+        };
+        Ok(self
+            .get_checkpoint_contents(&checkpoint.content_digest)?
+            .map(|contents| (checkpoint, contents)))
+    }
+
+    /// Returns how this node first obtained the certified checkpoint `seq`, if it is known.
+    pub fn get_checkpoint_provenance(
+        &self,
+        seq: CheckpointSequenceNumber,
+    ) -> Result<Option<CheckpointProvenance>, TypedStoreError> {
+        self.checkpoint_provenance.get(&seq)
+    }
+
     pub fn get_locally_computed_checkpoint(
         &self,
         sequence_number: CheckpointSequenceNumber,
@@ -231,6 +685,28 @@ impl CheckpointStore {
         self.checkpoint_sequence_by_contents_digest.remove(digest)
     }
 
+    /// Writes the contents digest to sequence number mapping of every certified checkpoint in
+    /// `[start, end]` again, to repair mappings deleted with
+    /// `delete_contents_digest_sequence_number_mapping`. Returns the number of mappings written.
+    pub fn rebuild_contents_digest_index(
+        &self,
+        start: CheckpointSequenceNumber,
+        end: CheckpointSequenceNumber,
+    ) -> SuiResult<u64> {
+        let mut batch = self.checkpoint_sequence_by_contents_digest.batch();
+        let mut count = 0;
+        for (seq, checkpoint) in self.certified_checkpoints.range_iter(start..=end) {
+            let checkpoint: VerifiedCheckpoint = checkpoint.into();
+            batch.insert_batch(
+                &self.checkpoint_sequence_by_contents_digest,
+                [(checkpoint.content_digest, seq)],
+            )?;
+            count += 1;
+        }
+        batch.write()?;
+        Ok(count)
+    }
+
     pub fn get_latest_certified_checkpoint(&self) -> Option<VerifiedCheckpoint> {
         self.certified_checkpoints
             .unbounded_iter()
@@ -239,6 +715,21 @@ impl CheckpointStore {
             .map(|(_, v)| v.into())
     }
 
+    /// Returns up to `n` of the highest certified checkpoints, in increasing sequence order.
+    /// Fewer checkpoints are returned if the store has fewer than `n`.
+    pub fn get_latest_checkpoints(&self, n: usize) -> SuiResult<Vec<VerifiedCheckpoint>> {
+        let mut checkpoints: Vec<VerifiedCheckpoint> = self
+            .certified_checkpoints
+            .unbounded_iter()
+            .skip_to_last()
+            .reverse()
+            .take(n)
+            .map(|(_, checkpoint)| checkpoint.into())
+            .collect();
+        checkpoints.reverse();
+        Ok(checkpoints)
+    }
+
     pub fn get_latest_locally_computed_checkpoint(&self) -> Option<CheckpointSummary> {
         self.locally_computed_checkpoints
             .unbounded_iter()
@@ -294,6 +785,20 @@ impl CheckpointStore {
         self.get_checkpoint_by_digest(&highest_synced.1)
     }
 
+    /// Returns the highest checkpoint whose full contents were stored through state sync. Full
+    /// contents may be missing for lower checkpoints, e.g. because they were pruned.
+    pub fn get_highest_checkpoint_with_contents(
+        &self,
+    ) -> Result<Option<VerifiedCheckpoint>, TypedStoreError> {
+        let Some((_, digest)) = self
+            .watermarks
+            .get(&CheckpointWatermark::HighestWithContents)?
+        else {
+            return Ok(None);
+        };
+        self.get_checkpoint_by_digest(&digest)
+    }
+
     pub fn get_highest_executed_checkpoint_seq_number(
         &self,
     ) -> Result<Option<CheckpointSequenceNumber>, TypedStoreError> {
@@ -329,6 +834,62 @@ impl CheckpointStore {
             .0)
     }
 
+    /// Returns how many checkpoints the highest pruned checkpoint is behind the highest executed
+    /// checkpoint.
+    pub fn get_checkpoint_pruning_lag(&self) -> Result<u64, TypedStoreError> {
+        let highest_executed = self
+            .get_highest_executed_checkpoint_seq_number()?
+            .unwrap_or_default();
+        let highest_pruned = self.get_highest_pruned_checkpoint_seq_number()?;
+        Ok(highest_executed.saturating_sub(highest_pruned))
+    }
+
+    /// Checks that the watermarks are ordered as
+    /// `HighestPruned <= HighestExecuted <= HighestSynced <= HighestVerified`, and returns an
+    /// error describing the first violation found otherwise, which indicates a corrupted store.
+    /// Watermarks that are not set yet are not checked, except `HighestPruned` which defaults
+    /// to 0.
+    pub fn validate_watermarks(&self) -> SuiResult {
+        let get_seq =
+            |watermark: CheckpointWatermark| -> SuiResult<Option<CheckpointSequenceNumber>> {
+                Ok(self.watermarks.get(&watermark)?.map(|(seq, _)| seq))
+            };
+        let ordered = [
+            (
+                CheckpointWatermark::HighestPruned,
+                Some(self.get_highest_pruned_checkpoint_seq_number()?),
+            ),
+            (
+                CheckpointWatermark::HighestExecuted,
+                get_seq(CheckpointWatermark::HighestExecuted)?,
+            ),
+            (
+                CheckpointWatermark::HighestSynced,
+                get_seq(CheckpointWatermark::HighestSynced)?,
+            ),
+            (
+                CheckpointWatermark::HighestVerified,
+                get_seq(CheckpointWatermark::HighestVerified)?,
+            ),
+        ];
+        for (i, (lower, lower_seq)) in ordered.iter().enumerate() {
+            let Some(lower_seq) = lower_seq else {
+                continue;
+            };
+            for (higher, higher_seq) in &ordered[i + 1..] {
+                if let Some(higher_seq) = higher_seq {
+                    if lower_seq > higher_seq {
+                        return Err(SuiError::GenericStorageError(format!(
+                            "Checkpoint watermark {lower:?} ({lower_seq}) is ahead of \
+                             {higher:?} ({higher_seq})"
+                        )));
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
     pub fn get_checkpoint_contents(
         &self,
         digest: &CheckpointContentsDigest,
@@ -336,6 +897,13 @@ impl CheckpointStore {
         self.checkpoint_content.get(digest)
     }
 
+    pub fn contains_checkpoint_contents(
+        &self,
+        digest: &CheckpointContentsDigest,
+    ) -> Result<bool, TypedStoreError> {
+        self.checkpoint_content.contains_key(digest)
+    }
+
     pub fn get_full_checkpoint_contents_by_sequence_number(
         &self,
         seq: CheckpointSequenceNumber,
@@ -343,6 +911,20 @@ impl CheckpointStore {
         self.full_checkpoint_content.get(&seq)
     }
 
+    pub fn multi_get_full_checkpoint_contents(
+        &self,
+        seqs: &[CheckpointSequenceNumber],
+    ) -> Result<Vec<Option<FullCheckpointContents>>, TypedStoreError> {
+        self.full_checkpoint_content.multi_get(seqs)
+    }
+
+    pub fn contains_full_checkpoint_contents(
+        &self,
+        seq: CheckpointSequenceNumber,
+    ) -> Result<bool, TypedStoreError> {
+        self.full_checkpoint_content.contains_key(&seq)
+    }
+
     fn prune_local_summaries(&self) -> SuiResult {
         if let Some((last_local_summary, _)) = self
             .locally_computed_checkpoints
@@ -350,18 +932,35 @@ impl CheckpointStore {
             .skip_to_last()
             .next()
         {
-            let mut batch = self.locally_computed_checkpoints.batch();
-            batch.schedule_delete_range(
-                &self.locally_computed_checkpoints,
-                &0,
-                &last_local_summary,
-            )?;
-            batch.write()?;
-            info!("Pruned local summaries up to {:?}", last_local_summary);
+            self.prune_local_summaries_to(last_local_summary)?;
         }
         Ok(())
     }
 
+    /// Deletes the locally computed checkpoint summaries below `keep_from`, so that a tail of
+    /// summaries can be kept for fork diagnostics.
+    pub fn prune_local_summaries_to(&self, keep_from: CheckpointSequenceNumber) -> SuiResult {
+        let mut batch = self.locally_computed_checkpoints.batch();
+        batch.schedule_delete_range(&self.locally_computed_checkpoints, &0, &keep_from)?;
+        batch.write()?;
+        info!("Pruned local summaries up to {:?}", keep_from);
+        Ok(())
+    }
+
+    /// Records `fake_local_summary` as the locally computed summary of checkpoint `seq`, so that
+    /// tests can make the fork check fail when the certified checkpoint `seq` is inserted,
+    /// without actually building a forked checkpoint.
+    #[cfg(any(test, feature = "test-utils"))]
+    pub fn inject_fork_for_test(
+        &self,
+        seq: CheckpointSequenceNumber,
+        fake_local_summary: &CheckpointSummary,
+    ) -> SuiResult {
+        self.locally_computed_checkpoints
+            .insert(&seq, fake_local_summary)?;
+        Ok(())
+    }
+
     fn check_for_checkpoint_fork(
         &self,
         local_checkpoint: &CheckpointSummary,
@@ -431,6 +1030,46 @@ impl CheckpointStore {
     pub fn insert_certified_checkpoint(
         &self,
         checkpoint: &VerifiedCheckpoint,
+    ) -> Result<(), TypedStoreError> {
+        self.insert_certified_checkpoint_with_provenance(checkpoint, None)
+    }
+
+    /// Checks that `checkpoint` can be inserted without overwriting a different certified
+    /// checkpoint with the same sequence number, and that an end of epoch checkpoint directly
+    /// follows the highest epoch recorded so far. The insert methods only write, so callers are
+    /// expected to run this check before inserting a checkpoint that was not built locally.
+    pub fn check_certified_checkpoint(&self, checkpoint: &VerifiedCheckpoint) -> SuiResult {
+        if let Some(existing) = self
+            .certified_checkpoints
+            .get(checkpoint.sequence_number())?
+        {
+            let existing: VerifiedCheckpoint = existing.into();
+            if existing.digest() != checkpoint.digest() {
+                error!(
+                    checkpoint_seq = checkpoint.sequence_number(),
+                    existing_digest = ?existing.digest(),
+                    new_digest = ?checkpoint.digest(),
+                    "Refusing to overwrite a certified checkpoint with a different digest"
+                );
+                return Err(SuiError::ConflictingCertifiedCheckpoint {
+                    sequence_number: *checkpoint.sequence_number(),
+                    existing: *existing.digest(),
+                    new: *checkpoint.digest(),
+                });
+            }
+        }
+        if checkpoint.next_epoch_committee().is_some() {
+            self.check_epoch_contiguity(checkpoint)?;
+        }
+        Ok(())
+    }
+
+    /// Inserts `checkpoint` like `insert_certified_checkpoint`, also recording `provenance` if
+    /// given, unless a provenance is already recorded for its sequence number.
+    pub fn insert_certified_checkpoint_with_provenance(
+        &self,
+        checkpoint: &VerifiedCheckpoint,
+        provenance: Option<CheckpointProvenance>,
     ) -> Result<(), TypedStoreError> {
         let mut batch = self.certified_checkpoints.batch();
         batch
@@ -441,7 +1080,22 @@ impl CheckpointStore {
             .insert_batch(
                 &self.checkpoint_by_digest,
                 [(checkpoint.digest(), checkpoint.serializable_ref())],
+            )?
+            .insert_batch(
+                &self.checkpoint_timestamps,
+                [(checkpoint.sequence_number(), checkpoint.timestamp_ms)],
             )?;
+        if let Some(provenance) = provenance {
+            if !self
+                .checkpoint_provenance
+                .contains_key(checkpoint.sequence_number())?
+            {
+                batch.insert_batch(
+                    &self.checkpoint_provenance,
+                    [(checkpoint.sequence_number(), provenance)],
+                )?;
+            }
+        }
         if checkpoint.next_epoch_committee().is_some() {
             batch.insert_batch(
                 &self.epoch_last_checkpoint_map,
@@ -460,13 +1114,54 @@ impl CheckpointStore {
         Ok(())
     }
 
+    /// Checks that the end of epoch `checkpoint` can be recorded as the last checkpoint of its
+    /// epoch, i.e. that its epoch directly follows the highest epoch recorded so far. Recording
+    /// the same checkpoint again is allowed, and any epoch is accepted when no epoch has been
+    /// recorded yet, which is the case for genesis and for stores restored from a snapshot.
+    fn check_epoch_contiguity(&self, checkpoint: &VerifiedCheckpoint) -> SuiResult {
+        let epoch = checkpoint.epoch();
+        if let Some(recorded) = self.epoch_last_checkpoint_map.get(&epoch)? {
+            if recorded == *checkpoint.sequence_number() {
+                return Ok(());
+            }
+            return Err(SuiError::GenericStorageError(format!(
+                "Checkpoint {} ends epoch {epoch}, which already ended at checkpoint {recorded}",
+                checkpoint.sequence_number()
+            )));
+        }
+        match self
+            .epoch_last_checkpoint_map
+            .unbounded_iter()
+            .skip_to_last()
+            .next()
+        {
+            Some((highest_epoch, _)) if epoch != highest_epoch + 1 => {
+                Err(SuiError::GenericStorageError(format!(
+                    "Checkpoint {} ends epoch {epoch}, last recorded epoch is {highest_epoch}",
+                    checkpoint.sequence_number()
+                )))
+            }
+            _ => Ok(()),
+        }
+    }
+
     // Called by state sync, apart from inserting the checkpoint and updating
     // related tables, it also bumps the highest_verified_checkpoint watermark.
     pub fn insert_verified_checkpoint(
         &self,
         checkpoint: &VerifiedCheckpoint,
     ) -> Result<(), TypedStoreError> {
-        self.insert_certified_checkpoint(checkpoint)?;
+        self.insert_verified_checkpoint_with_provenance(checkpoint, None)
+    }
+
+    /// Inserts `checkpoint` like `insert_verified_checkpoint`, also recording `provenance` if
+    /// given, unless a provenance is already recorded for its sequence number.
+    pub fn insert_verified_checkpoint_with_provenance(
+        &self,
+        checkpoint: &VerifiedCheckpoint,
+        provenance: Option<CheckpointProvenance>,
+    ) -> Result<(), TypedStoreError> {
+        self.insert_certified_checkpoint_with_provenance(checkpoint, provenance)?;
         self.update_highest_verified_checkpoint(checkpoint)
     }
 
@@ -517,14 +1212,43 @@ impl CheckpointStore {
         )
     }
 
+    /// Sets the highest pruned checkpoint watermark and reports it to `metrics`. Pruning past the
+    /// highest executed checkpoint would remove data that is still needed, so depending on
+    /// `on_violation` this either fails without writing the watermark or logs an error and
+    /// writes it anyway.
     pub fn update_highest_pruned_checkpoint(
         &self,
         checkpoint: &VerifiedCheckpoint,
-    ) -> Result<(), TypedStoreError> {
+        on_violation: PrunedWatermarkViolation,
+        metrics: &CheckpointMetrics,
+    ) -> SuiResult {
+        let highest_executed = self.get_highest_executed_checkpoint_seq_number()?;
+        if highest_executed.map_or(true, |executed| *checkpoint.sequence_number() > executed) {
+            let message = format!(
+                "Highest pruned checkpoint {} would exceed highest executed checkpoint {:?}",
+                checkpoint.sequence_number(),
+                highest_executed
+            );
+            match on_violation {
+                PrunedWatermarkViolation::Error => {
+                    return Err(SuiError::GenericStorageError(message));
+                }
+                PrunedWatermarkViolation::Warn => error!("{}", message),
+            }
+        }
         self.watermarks.insert(
             &CheckpointWatermark::HighestPruned,
             &(*checkpoint.sequence_number(), *checkpoint.digest()),
-        )
+        )?;
+        metrics
+            .highest_pruned_checkpoint
+            .set(*checkpoint.sequence_number() as i64);
+        metrics.checkpoint_pruning_lag.set(
+            highest_executed
+                .unwrap_or_default()
+                .saturating_sub(*checkpoint.sequence_number()) as i64,
+        );
+        Ok(())
     }
 
     /// Sets highest executed checkpoint to any value.
@@ -569,6 +1293,20 @@ impl CheckpointStore {
 
         batch.insert_batch(&self.checkpoint_content, [(contents.digest(), &contents)])?;
 
+        let highest_with_contents = self
+            .watermarks
+            .get(&CheckpointWatermark::HighestWithContents)?
+            .map(|(seq, _)| seq);
+        if Some(*checkpoint.sequence_number()) > highest_with_contents {
+            batch.insert_batch(
+                &self.watermarks,
+                [(
+                    CheckpointWatermark::HighestWithContents,
+                    (*checkpoint.sequence_number(), *checkpoint.digest()),
+                )],
+            )?;
+        }
+
         batch.write()
     }
 
@@ -579,10 +1317,82 @@ impl CheckpointStore {
         self.full_checkpoint_content.remove(&seq)
     }
 
-    pub fn get_epoch_last_checkpoint(
-        &self,
-        epoch_id: EpochId,
-    ) -> SuiResult<Option<VerifiedCheckpoint>> {
+    /// Deletes all full checkpoint contents except for the `keep_last` highest sequence numbers
+    /// present in the table, in a single range deletion.
+    pub fn prune_full_checkpoint_contents(&self, keep_last: u64) -> SuiResult {
+        let Some((highest, _)) = self
+            .full_checkpoint_content
+            .unbounded_iter()
+            .skip_to_last()
+            .next()
+        else {
+            return Ok(());
+        };
+        let cutoff = (highest + 1).saturating_sub(keep_last);
+        let Some(lowest) = self.full_checkpoint_content.keys().next().transpose()? else {
+            return Ok(());
+        };
+        if lowest >= cutoff {
+            return Ok(());
+        }
+        let mut batch = self.full_checkpoint_content.batch();
+        batch.schedule_delete_range(&self.full_checkpoint_content, &0, &cutoff)?;
+        batch.write()?;
+        Ok(())
+    }
+
+    /// Estimates how many bytes pruning the checkpoints below `target_seq` would free in the
+    /// `certified_checkpoints`, `checkpoint_content` and `full_checkpoint_content` tables. The
+    /// estimate is approximate: it multiplies the number of entries below `target_seq` by the
+    /// average entry size of each table, and assumes one contents entry per certified
+    /// checkpoint. Both come from the RocksDB estimates of the number of keys and of the live
+    /// data size of each table, so no table is scanned.
+    pub fn estimate_prune_savings(&self, target_seq: CheckpointSequenceNumber) -> SuiResult<u64> {
+        let certified = estimate_entries_below(&self.certified_checkpoints, target_seq)?;
+        let full = estimate_entries_below(&self.full_checkpoint_content, target_seq)?;
+        Ok(average_entry_size(&self.certified_checkpoints)? * certified
+            + average_entry_size(&self.checkpoint_content)? * certified
+            + average_entry_size(&self.full_checkpoint_content)? * full)
+    }
+
+    /// Returns the sequence number of the last checkpoint of every epoch recorded in the store,
+    /// sorted by epoch.
+    pub fn all_epoch_last_checkpoints(
+        &self,
+    ) -> SuiResult<Vec<(EpochId, CheckpointSequenceNumber)>> {
+        Ok(self.epoch_last_checkpoint_map.unbounded_iter().collect())
+    }
+
+    /// Returns the epochs whose first certified checkpoint does not link back to the last
+    /// certified checkpoint of the previous epoch through its `previous_digest`, or does not
+    /// belong to the epoch following it. Boundaries for which either checkpoint is missing from
+    /// the store are skipped.
+    pub fn verify_epoch_boundary_links(&self) -> SuiResult<Vec<EpochId>> {
+        let mut broken = vec![];
+        for (epoch, last_seq) in self.epoch_last_checkpoint_map.unbounded_iter() {
+            let (Some(last), Some(first)) = (
+                self.get_checkpoint_by_sequence_number(last_seq)?,
+                self.get_checkpoint_by_sequence_number(last_seq + 1)?,
+            ) else {
+                continue;
+            };
+            if first.previous_digest != Some(*last.digest()) || first.epoch() != epoch + 1 {
+                warn!(
+                    checkpoint_seq = first.sequence_number,
+                    "First checkpoint of epoch {} does not link to last checkpoint {} of epoch {epoch}",
+                    epoch + 1,
+                    last_seq
+                );
+                broken.push(epoch + 1);
+            }
+        }
+        Ok(broken)
+    }
+
+    pub fn get_epoch_last_checkpoint(
+        &self,
+        epoch_id: EpochId,
+    ) -> SuiResult<Option<VerifiedCheckpoint>> {
         let seq = self.epoch_last_checkpoint_map.get(&epoch_id)?;
         let checkpoint = match seq {
             Some(seq) => self.get_checkpoint_by_sequence_number(seq)?,
@@ -591,6 +1401,356 @@ impl CheckpointStore {
         Ok(checkpoint)
     }
 
+    fn get_epoch_first_checkpoint_sequence(
+        &self,
+        epoch_id: EpochId,
+    ) -> Result<Option<CheckpointSequenceNumber>, TypedStoreError> {
+        if epoch_id == 0 {
+            return Ok(Some(0));
+        }
+        Ok(self
+            .epoch_last_checkpoint_map
+            .get(&(epoch_id - 1))?
+            .map(|seq| seq + 1))
+    }
+
+    /// Returns the sequence number of the checkpoint at `offset` from the first checkpoint of
+    /// the given epoch. For an epoch that has not ended yet, the offset is bounded by the highest
+    /// certified checkpoint instead of the last checkpoint of the epoch. Returns `None` if the
+    /// offset is past the checkpoints of the epoch, or if the start of the epoch is unknown.
+    pub fn checkpoint_at_epoch_offset(
+        &self,
+        epoch_id: EpochId,
+        offset: u64,
+    ) -> SuiResult<Option<CheckpointSequenceNumber>> {
+        let Some(first) = self.get_epoch_first_checkpoint_sequence(epoch_id)? else {
+            return Ok(None);
+        };
+        let last = match self.epoch_last_checkpoint_map.get(&epoch_id)? {
+            Some(last) => Some(last),
+            None => self
+                .get_latest_certified_checkpoint()
+                .filter(|checkpoint| checkpoint.epoch() == epoch_id)
+                .map(|checkpoint| *checkpoint.sequence_number()),
+        };
+        Ok(last.and_then(|last| first.checked_add(offset).filter(|seq| *seq <= last)))
+    }
+
+    /// Returns the first checkpoint of the given epoch, i.e. the checkpoint following the last
+    /// checkpoint of the previous epoch, or checkpoint 0 for epoch 0.
+    pub fn get_epoch_first_checkpoint(
+        &self,
+        epoch_id: EpochId,
+    ) -> SuiResult<Option<VerifiedCheckpoint>> {
+        let seq = self.get_epoch_first_checkpoint_sequence(epoch_id)?;
+        let checkpoint = match seq {
+            Some(seq) => self.get_checkpoint_by_sequence_number(seq)?,
+            None => None,
+        };
+        Ok(checkpoint)
+    }
+
+    /// Returns the epoch of the checkpoint `seq`. The epoch is read from the certified summary
+    /// if it is stored, and otherwise derived from the recorded epoch boundaries, which covers
+    /// checkpoints of ended epochs that were pruned. Returns None for checkpoints of the current
+    /// epoch that are not certified locally yet.
+    pub fn epoch_of_checkpoint(&self, seq: CheckpointSequenceNumber) -> SuiResult<Option<EpochId>> {
+        if let Some(checkpoint) = self.get_checkpoint_by_sequence_number(seq)? {
+            return Ok(Some(checkpoint.epoch()));
+        }
+        Ok(self
+            .epoch_last_checkpoint_map
+            .unbounded_iter()
+            .find(|(_, last)| *last >= seq)
+            .map(|(epoch, _)| epoch))
+    }
+
+    /// Returns an iterator over the certified checkpoints of the given epoch, in sequence order.
+    /// The iterator is empty if the epoch has not ended yet, or if the end of the previous epoch
+    /// is not recorded. The returned iterator borrows the store and reads from the database
+    /// lazily as it is advanced.
+    pub fn iter_epoch_checkpoints(
+        &self,
+        epoch_id: EpochId,
+    ) -> SuiResult<impl Iterator<Item = VerifiedCheckpoint> + '_> {
+        let first = self.get_epoch_first_checkpoint_sequence(epoch_id)?;
+        let last = self.epoch_last_checkpoint_map.get(&epoch_id)?;
+        let bounds = first.zip(last);
+        Ok(bounds
+            .into_iter()
+            .flat_map(move |(first, last)| self.certified_checkpoints.range_iter(first..=last))
+            .map(|(_, checkpoint)| checkpoint.into()))
+    }
+
+    /// Returns the timestamp of the certified checkpoint at `seq`. Checkpoints certified before
+    /// timestamps were indexed separately fall back to reading the full summary.
+    pub fn get_checkpoint_timestamp(
+        &self,
+        seq: CheckpointSequenceNumber,
+    ) -> Result<Option<CheckpointTimestamp>, TypedStoreError> {
+        if let Some(timestamp) = self.checkpoint_timestamps.get(&seq)? {
+            return Ok(Some(timestamp));
+        }
+        Ok(self
+            .get_checkpoint_by_sequence_number(seq)?
+            .map(|checkpoint| checkpoint.timestamp_ms))
+    }
+
+    /// Returns the timestamp of the certified checkpoint at `seq`, or `None` if it is not
+    /// certified. Same as `get_checkpoint_timestamp`, with storage errors as `SuiError`.
+    pub fn checkpoint_timestamp(
+        &self,
+        seq: CheckpointSequenceNumber,
+    ) -> SuiResult<Option<CheckpointTimestamp>> {
+        Ok(self.get_checkpoint_timestamp(seq)?)
+    }
+
+    /// Returns the summary of the certified checkpoint at `seq` encoded as JSON. Digests, public
+    /// keys and 64 bit amounts are encoded with the human readable representations of their
+    /// serde implementations, e.g. digests as base58 strings and amounts as decimal strings.
+    pub fn get_checkpoint_summary_json(
+        &self,
+        seq: CheckpointSequenceNumber,
+    ) -> SuiResult<Option<String>> {
+        let Some(checkpoint) = self.get_checkpoint_by_sequence_number(seq)? else {
+            return Ok(None);
+        };
+        let json = serde_json::to_string(checkpoint.data())
+            .map_err(|e| SuiError::GenericStorageError(e.to_string()))?;
+        Ok(Some(json))
+    }
+
+    /// Returns whether our checkpoint at `seq` has the digest reported by a peer. The certified
+    /// checkpoint is used if we have one, otherwise the locally built one. Returns `None` if we
+    /// have neither.
+    pub fn agrees_with_peer(
+        &self,
+        seq: CheckpointSequenceNumber,
+        peer_digest: CheckpointDigest,
+    ) -> SuiResult<Option<bool>> {
+        let our_digest = match self.get_checkpoint_by_sequence_number(seq)? {
+            Some(checkpoint) => Some(*checkpoint.digest()),
+            None => self
+                .locally_computed_checkpoints
+                .get(&seq)?
+                .map(|summary| summary.digest()),
+        };
+        Ok(our_digest.map(|digest| digest == peer_digest))
+    }
+
+    /// Returns whether `digest` is the digest of a certified checkpoint, of a checkpoint that was
+    /// only built locally, or of neither. Locally built checkpoints are keyed by sequence number,
+    /// so the sequence number the digest is claimed for is needed to look them up.
+    pub fn checkpoint_status(
+        &self,
+        seq: CheckpointSequenceNumber,
+        digest: &CheckpointDigest,
+    ) -> SuiResult<CheckpointStatus> {
+        if self.checkpoint_by_digest.contains_key(digest)? {
+            return Ok(CheckpointStatus::Certified);
+        }
+        if let Some(summary) = self.locally_computed_checkpoints.get(&seq)? {
+            if summary.digest() == *digest {
+                return Ok(CheckpointStatus::LocallyBuiltOnly);
+            }
+        }
+        Ok(CheckpointStatus::Unknown)
+    }
+
+    /// Reconstructs the committee of the given epoch from the `next_epoch_committee` declared by
+    /// the last checkpoint of the previous epoch. For epoch 0, returns the genesis committee
+    /// recorded by `insert_genesis_committee`, or `None` if it was never recorded. Returns an
+    /// error if the last checkpoint of the previous epoch is not available.
+    pub fn committee_for_epoch(&self, epoch_id: EpochId) -> SuiResult<Option<Committee>> {
+        if epoch_id == 0 {
+            return Ok(self.genesis_committee.get(&())?);
+        }
+        let checkpoint = self
+            .get_epoch_last_checkpoint(epoch_id - 1)?
+            .ok_or_else(|| {
+                SuiError::GenericStorageError(format!(
+                    "Last checkpoint of epoch {} is not available",
+                    epoch_id - 1
+                ))
+            })?;
+        let next_epoch_committee = checkpoint.next_epoch_committee().ok_or_else(|| {
+            SuiError::GenericStorageError(format!(
+                "Last checkpoint of epoch {} has no end of epoch data",
+                epoch_id - 1
+            ))
+        })?;
+        Ok(Some(Committee::new(
+            epoch_id,
+            next_epoch_committee.iter().cloned().collect(),
+        )))
+    }
+
+    /// Returns the signers of the certified checkpoint at `seq` and their stake, or `None` if
+    /// the checkpoint is not certified. Fails if the committee of the checkpoint's epoch can't be
+    /// reconstructed (see `committee_for_epoch`).
+    pub fn certification_details(
+        &self,
+        seq: CheckpointSequenceNumber,
+    ) -> SuiResult<Option<CertificationInfo>> {
+        let Some(checkpoint) = self.get_checkpoint_by_sequence_number(seq)? else {
+            return Ok(None);
+        };
+        let committee = self
+            .committee_for_epoch(checkpoint.epoch())?
+            .ok_or_else(|| {
+                SuiError::GenericStorageError(format!(
+                    "Committee of epoch {} is not recorded in checkpoints",
+                    checkpoint.epoch()
+                ))
+            })?;
+        let signers = checkpoint
+            .auth_sig()
+            .authorities(&committee)
+            .map(|name| name.copied())
+            .collect::<SuiResult<Vec<_>>>()?;
+        let signing_stake = signers.iter().map(|name| committee.weight(name)).sum();
+        let total_stake = committee.total_votes();
+        Ok(Some(CertificationInfo {
+            signers,
+            signing_stake,
+            signing_stake_fraction: signing_stake as f64 / total_stake as f64,
+            total_stake,
+        }))
+    }
+
+    /// Returns up to `limit` certified checkpoints with a sequence number greater than `after`
+    /// (or from the lowest one if `after` is `None`), in order, along with the cursor to pass as
+    /// `after` to get the next page. The cursor is `None` once there are no more checkpoints.
+    pub fn get_checkpoints_paginated(
+        &self,
+        after: Option<CheckpointSequenceNumber>,
+        limit: usize,
+    ) -> SuiResult<(Vec<VerifiedCheckpoint>, Option<CheckpointSequenceNumber>)> {
+        let start = match after {
+            Some(after) => match after.checked_add(1) {
+                Some(start) => start,
+                None => return Ok((vec![], None)),
+            },
+            None => 0,
+        };
+        // Read one extra checkpoint to know whether there is a next page.
+        let mut checkpoints: Vec<VerifiedCheckpoint> = self
+            .certified_checkpoints
+            .range_iter(start..)
+            .take(limit.saturating_add(1))
+            .map(|(_, checkpoint)| checkpoint.into())
+            .collect();
+        let cursor = if checkpoints.len() > limit {
+            checkpoints.truncate(limit);
+            checkpoints
+                .last()
+                .map(|checkpoint| *checkpoint.sequence_number())
+        } else {
+            None
+        };
+        Ok((checkpoints, cursor))
+    }
+
+    /// Returns a stream of the headers of the certified checkpoints from `start` up to the
+    /// highest certified checkpoint, in order. Checkpoints are read from the database and
+    /// projected into headers lazily as the stream is polled.
+    pub fn stream_headers_from(
+        &self,
+        start: CheckpointSequenceNumber,
+    ) -> impl Stream<Item = CheckpointHeader> + '_ {
+        stream::iter(
+            self.certified_checkpoints
+                .range_iter(start..)
+                .map(|(_, checkpoint)| {
+                    let checkpoint: VerifiedCheckpoint = checkpoint.into();
+                    CheckpointHeader::from(&checkpoint)
+                }),
+        )
+    }
+
+    /// Returns a stream of the full checkpoint contents stored for the checkpoints in
+    /// `[start, end]`, in order. Checkpoints whose full contents are not stored are skipped.
+    /// Entries are read from the database one at a time as the stream is polled, so the whole
+    /// range is never held in memory.
+    pub fn stream_full_contents(
+        &self,
+        start: CheckpointSequenceNumber,
+        end: CheckpointSequenceNumber,
+    ) -> impl Stream<Item = SuiResult<(CheckpointSequenceNumber, FullCheckpointContents)>> + '_
+    {
+        stream::iter(
+            self.full_checkpoint_content
+                .safe_range_iter(start..=end)
+                .map(|entry| entry.map_err(SuiError::from)),
+        )
+    }
+
+    /// Returns the members of the committee of the given epoch that did not sign any of the
+    /// certified checkpoints of that epoch stored locally. For an epoch that has not ended yet,
+    /// this covers the checkpoints certified so far. Fails if the committee of the epoch can't be
+    /// reconstructed (see `committee_for_epoch`) or if the start of the epoch is unknown.
+    pub fn absent_validators_in_epoch(&self, epoch_id: EpochId) -> SuiResult<Vec<AuthorityName>> {
+        let committee = self.committee_for_epoch(epoch_id)?.ok_or_else(|| {
+            SuiError::GenericStorageError(format!(
+                "Committee of epoch {epoch_id} is not recorded in checkpoints"
+            ))
+        })?;
+        let first = self
+            .get_epoch_first_checkpoint_sequence(epoch_id)?
+            .ok_or_else(|| {
+                SuiError::GenericStorageError(format!(
+                    "First checkpoint of epoch {epoch_id} is unknown"
+                ))
+            })?;
+        let mut signers = HashSet::new();
+        for (_, checkpoint) in self.certified_checkpoints.range_iter(first..) {
+            let checkpoint: VerifiedCheckpoint = checkpoint.into();
+            if checkpoint.epoch() != epoch_id {
+                break;
+            }
+            for name in checkpoint.auth_sig().authorities(&committee) {
+                signers.insert(*name?);
+            }
+            if signers.len() == committee.num_members() {
+                break;
+            }
+        }
+        Ok(committee
+            .names()
+            .filter(|name| !signers.contains(*name))
+            .copied()
+            .collect())
+    }
+
+    /// Returns the number of epochs for which the last checkpoint has been recorded.
+    pub fn completed_epoch_count(&self) -> SuiResult<u64> {
+        Ok(self.epoch_last_checkpoint_map.keys().count() as u64)
+    }
+
+    /// Finds the last checkpoint of the given epoch by scanning certified checkpoints backwards
+    /// from the highest one. Unlike `get_epoch_last_checkpoint`, this does not rely on the epoch
+    /// boundary map, which may be incomplete on a node restored from a snapshot.
+    pub fn find_epoch_last_certified_checkpoint(
+        &self,
+        epoch_id: EpochId,
+    ) -> SuiResult<Option<VerifiedCheckpoint>> {
+        for (_, checkpoint) in self
+            .certified_checkpoints
+            .unbounded_iter()
+            .skip_to_last()
+            .reverse()
+        {
+            let checkpoint: VerifiedCheckpoint = checkpoint.into();
+            if checkpoint.epoch() < epoch_id {
+                break;
+            }
+            if checkpoint.epoch() == epoch_id && checkpoint.end_of_epoch_data.is_some() {
+                return Ok(Some(checkpoint));
+            }
+        }
+        Ok(None)
+    }
+
     pub fn insert_epoch_last_checkpoint(
         &self,
         epoch_id: EpochId,
@@ -617,16 +1777,421 @@ impl CheckpointStore {
         } else {
             return None;
         };
+        let gas_cost_summary = &last_checkpoint.epoch_rolling_gas_cost_summary;
         Some(EpochStats {
             checkpoint_count: last_checkpoint.sequence_number - first_checkpoint + 1,
             transaction_count: last_checkpoint.network_total_transactions
                 - prev_epoch_network_transactions,
-            total_gas_reward: last_checkpoint
-                .epoch_rolling_gas_cost_summary
-                .computation_cost,
+            total_gas_reward: gas_cost_summary.computation_cost,
+            total_storage_cost: gas_cost_summary.storage_cost,
+            total_storage_rebate: gas_cost_summary.storage_rebate,
+            total_non_refundable_storage_fee: gas_cost_summary.non_refundable_storage_fee,
+        })
+    }
+
+    /// Same as `get_epoch_stats` for an epoch that may still be in progress, with the statistics
+    /// computed up to and including the checkpoint `up_to` of that epoch. The statistics are
+    /// provisional until the epoch ends. Returns None if `up_to` is not in `epoch`, or if the
+    /// end of the previous epoch is unknown.
+    pub fn get_partial_epoch_stats(
+        &self,
+        epoch: EpochId,
+        up_to: &CheckpointSummary,
+    ) -> Option<EpochStats> {
+        if up_to.epoch != epoch {
+            return None;
+        }
+        self.get_epoch_stats(epoch, up_to)
+    }
+
+    pub fn insert_epoch_stats(&self, epoch: EpochId, stats: &EpochStats) -> SuiResult {
+        self.epoch_stats.insert(&epoch, stats)?;
+        Ok(())
+    }
+
+    /// Appends `failure` to the `build_failures` table, dropping the oldest failures beyond
+    /// `MAX_RECORDED_BUILD_FAILURES`.
+    pub fn record_build_failure(&self, failure: &BuildFailure) -> SuiResult {
+        let index = self
+            .build_failures
+            .unbounded_iter()
+            .skip_to_last()
+            .next()
+            .map(|(index, _)| index + 1)
+            .unwrap_or_default();
+        let mut batch = self.build_failures.batch();
+        batch.insert_batch(&self.build_failures, [(index, failure)])?;
+        if index >= MAX_RECORDED_BUILD_FAILURES {
+            batch.schedule_delete_range(
+                &self.build_failures,
+                &0,
+                &(index + 1 - MAX_RECORDED_BUILD_FAILURES),
+            )?;
+        }
+        batch.write()?;
+        Ok(())
+    }
+
+    /// Returns the recorded checkpoint build failures, oldest first.
+    pub fn recent_build_failures(&self) -> SuiResult<Vec<BuildFailure>> {
+        Ok(self
+            .build_failures
+            .unbounded_iter()
+            .map(|(_, failure)| failure)
+            .collect())
+    }
+
+    /// Returns the statistics persisted for `epoch` when its last checkpoint was built, if any.
+    pub fn get_stored_epoch_stats(&self, epoch: EpochId) -> SuiResult<Option<EpochStats>> {
+        Ok(self.epoch_stats.get(&epoch)?)
+    }
+
+    /// Returns the gas cost of each certified checkpoint in `[start, end]` paired with the
+    /// checkpoint timestamp. The cost of a checkpoint is derived from the difference between its
+    /// epoch rolling gas cost summary and the one of its predecessor, taking into account that the
+    /// rolling summary is reset at every epoch boundary. Checkpoints whose predecessor is not
+    /// available (e.g. because it was pruned) are skipped.
+    pub fn export_gas_timeseries(
+        &self,
+        start: CheckpointSequenceNumber,
+        end: CheckpointSequenceNumber,
+    ) -> SuiResult<Vec<(CheckpointTimestamp, GasCostSummary)>> {
+        let mut result = vec![];
+        let mut previous: Option<VerifiedCheckpoint> = None;
+        for (seq, checkpoint) in self.certified_checkpoints.range_iter(start..=end) {
+            let checkpoint: VerifiedCheckpoint = checkpoint.into();
+            if seq > 0 && previous.as_ref().map(|c| c.sequence_number + 1) != Some(seq) {
+                previous = self.get_checkpoint_by_sequence_number(seq - 1)?;
+            }
+            if seq == 0 || previous.is_some() {
+                let gas_cost =
+                    checkpoint_gas_cost(previous.as_ref().map(|c| c.data()), checkpoint.data());
+                result.push((checkpoint.timestamp_ms, gas_cost));
+            }
+            previous = Some(checkpoint);
+        }
+        Ok(result)
+    }
+
+    /// Returns the mean time in milliseconds between consecutive certified checkpoints in
+    /// `[start, end]`, computed from the timestamps of the two endpoints. Returns `None` if the
+    /// range has no intervals or either endpoint is not available.
+    pub fn average_checkpoint_interval_ms(
+        &self,
+        start: CheckpointSequenceNumber,
+        end: CheckpointSequenceNumber,
+    ) -> SuiResult<Option<f64>> {
+        if end <= start {
+            return Ok(None);
+        }
+        let (Some(first), Some(last)) = (
+            self.get_checkpoint_by_sequence_number(start)?,
+            self.get_checkpoint_by_sequence_number(end)?,
+        ) else {
+            return Ok(None);
+        };
+        let elapsed_ms = last.timestamp_ms.saturating_sub(first.timestamp_ms);
+        Ok(Some(elapsed_ms as f64 / (end - start) as f64))
+    }
+
+    /// Returns the sequence number of the first certified checkpoint in `[start, end]` whose
+    /// timestamp is lower than the one of the checkpoint preceding it in the range, or `None` if
+    /// the timestamps never decrease. Checkpoints missing from the store are skipped.
+    pub fn verify_timestamp_monotonicity(
+        &self,
+        start: CheckpointSequenceNumber,
+        end: CheckpointSequenceNumber,
+    ) -> SuiResult<Option<CheckpointSequenceNumber>> {
+        let mut previous: Option<CheckpointTimestamp> = None;
+        for (seq, checkpoint) in self.certified_checkpoints.range_iter(start..=end) {
+            let checkpoint: VerifiedCheckpoint = checkpoint.into();
+            let timestamp_ms = checkpoint.timestamp_ms;
+            if previous.is_some_and(|previous| timestamp_ms < previous) {
+                return Ok(Some(seq));
+            }
+            previous = Some(timestamp_ms);
+        }
+        Ok(None)
+    }
+
+    /// Checks that the stored contents of the certified checkpoint at `seq` hash to the content
+    /// digest recorded in its summary. Returns `Ok(false)` on mismatch, and an error if either
+    /// the checkpoint or its contents are missing.
+    pub fn verify_contents_integrity(&self, seq: CheckpointSequenceNumber) -> SuiResult<bool> {
+        let checkpoint = self
+            .get_checkpoint_by_sequence_number(seq)?
+            .ok_or(UserInputError::VerifiedCheckpointNotFound(seq))?;
+        let contents = self
+            .get_checkpoint_contents(&checkpoint.content_digest)?
+            .ok_or(UserInputError::CheckpointContentsNotFound(
+                checkpoint.content_digest,
+            ))?;
+        Ok(*contents.digest() == checkpoint.content_digest)
+    }
+
+    /// Returns the sequence numbers in `[start, end]` for which no certified checkpoint is stored.
+    pub fn find_sequence_gaps(
+        &self,
+        start: CheckpointSequenceNumber,
+        end: CheckpointSequenceNumber,
+    ) -> SuiResult<Vec<CheckpointSequenceNumber>> {
+        let mut gaps = vec![];
+        let mut expected = start;
+        for seq in self.certified_checkpoints.keys().skip_to(&start)? {
+            let seq = seq?;
+            if seq > end {
+                break;
+            }
+            gaps.extend(expected..seq);
+            expected = seq + 1;
+        }
+        if expected <= end {
+            gaps.extend(expected..=end);
+        }
+        Ok(gaps)
+    }
+
+    /// Returns the highest sequence number up to the executed watermark such that every
+    /// certified checkpoint from the pruning watermark to it is present, i.e. the checkpoint just
+    /// before the first gap. Pruned checkpoints are not counted as gaps. Returns None if nothing
+    /// has been executed, or if nothing was pruned and checkpoint 0 itself is missing.
+    pub fn highest_contiguous_executed(&self) -> SuiResult<Option<CheckpointSequenceNumber>> {
+        let Some(executed) = self.get_highest_executed_checkpoint_seq_number()? else {
+            return Ok(None);
+        };
+        let start = self
+            .watermarks
+            .get(&CheckpointWatermark::HighestPruned)?
+            .map(|(pruned, _)| pruned + 1)
+            .unwrap_or_default();
+        let mut expected = start;
+        for seq in self.certified_checkpoints.keys().skip_to(&start)? {
+            let seq = seq?;
+            if seq != expected || seq > executed {
+                break;
+            }
+            expected = seq + 1;
+        }
+        // `expected` is the first gap, or the checkpoint after the executed watermark.
+        Ok(if expected > executed {
+            Some(executed)
+        } else {
+            expected.checked_sub(1)
         })
     }
 
+    /// Returns a single hash summarizing the certified checkpoints in `[start, end]`, so that two
+    /// nodes can compare a range without exchanging every digest, and bisect it if they differ.
+    /// The hash is the Blake2b256 of, for each checkpoint in increasing sequence order, its
+    /// sequence number as 8 little endian bytes followed by the 32 bytes of its digest. Returns
+    /// an error if any checkpoint of the range is missing.
+    pub fn range_digest(
+        &self,
+        start: CheckpointSequenceNumber,
+        end: CheckpointSequenceNumber,
+    ) -> SuiResult<[u8; 32]> {
+        let mut hasher = DefaultHash::default();
+        let mut expected = start;
+        for (seq, checkpoint) in self.certified_checkpoints.range_iter(start..=end) {
+            if seq != expected {
+                return Err(UserInputError::VerifiedCheckpointNotFound(expected).into());
+            }
+            let checkpoint: VerifiedCheckpoint = checkpoint.into();
+            hasher.update(seq.to_le_bytes());
+            hasher.update(checkpoint.digest().inner());
+            expected = seq + 1;
+        }
+        if expected <= end {
+            return Err(UserInputError::VerifiedCheckpointNotFound(expected).into());
+        }
+        Ok(hasher.finalize().digest)
+    }
+
+    /// Returns the number of transactions in the certified checkpoint `seq`, computed from the
+    /// `network_total_transactions` counter of that checkpoint and of the previous one, without
+    /// reading the checkpoint contents. Returns None if either checkpoint is not in the store.
+    pub fn checkpoint_transaction_delta(
+        &self,
+        seq: CheckpointSequenceNumber,
+    ) -> SuiResult<Option<u64>> {
+        let Some(checkpoint) = self.get_checkpoint_by_sequence_number(seq)? else {
+            return Ok(None);
+        };
+        if seq == 0 {
+            return Ok(Some(checkpoint.network_total_transactions));
+        }
+        let Some(previous) = self.get_checkpoint_by_sequence_number(seq - 1)? else {
+            return Ok(None);
+        };
+        Ok(Some(
+            checkpoint.network_total_transactions - previous.network_total_transactions,
+        ))
+    }
+
+    /// Returns the number of transactions in the certified checkpoint `seq`. The count is taken
+    /// from `checkpoint_transaction_delta` when the previous checkpoint is available, and the
+    /// contents are only loaded otherwise. Returns None if the checkpoint, or the contents it
+    /// would have to fall back to, are not in the store.
+    pub fn get_checkpoint_transaction_count(
+        &self,
+        seq: CheckpointSequenceNumber,
+    ) -> SuiResult<Option<u64>> {
+        if let Some(count) = self.checkpoint_transaction_delta(seq)? {
+            return Ok(Some(count));
+        }
+        let Some(checkpoint) = self.get_checkpoint_by_sequence_number(seq)? else {
+            return Ok(None);
+        };
+        Ok(self
+            .get_checkpoint_contents(&checkpoint.content_digest)?
+            .map(|contents| contents.size() as u64))
+    }
+
+    /// Runs `verify_contents_integrity` over the certified checkpoints in `[start, end]` and
+    /// returns the sequence numbers of the checkpoints whose contents are corrupted or missing.
+    /// Checkpoints missing from the store are skipped, and ranges whose contents have been
+    /// pruned should not be scrubbed.
+    pub fn scrub_range(
+        &self,
+        start: CheckpointSequenceNumber,
+        end: CheckpointSequenceNumber,
+    ) -> SuiResult<Vec<CheckpointSequenceNumber>> {
+        let mut mismatches = vec![];
+        for (seq, checkpoint) in self.certified_checkpoints.range_iter(start..=end) {
+            let checkpoint: VerifiedCheckpoint = checkpoint.into();
+            let content_digest = checkpoint.content_digest;
+            let intact = self
+                .get_checkpoint_contents(&content_digest)?
+                .is_some_and(|contents| *contents.digest() == content_digest);
+            if !intact {
+                warn!(
+                    checkpoint_seq = seq,
+                    "Checkpoint contents do not match content digest {:?}", content_digest
+                );
+                mismatches.push(seq);
+            }
+        }
+        Ok(mismatches)
+    }
+
+    /// Returns the certified checkpoints in `[start, end]` whose BCS serialized contents are
+    /// larger than `threshold_bytes`. This loads and serializes the contents of every checkpoint
+    /// of the range, so it should only be run over bounded ranges and off the critical path.
+    /// Checkpoints missing from the store are skipped, but a checkpoint whose contents are
+    /// missing is an error.
+    pub fn find_oversized_checkpoints(
+        &self,
+        start: CheckpointSequenceNumber,
+        end: CheckpointSequenceNumber,
+        threshold_bytes: usize,
+    ) -> SuiResult<Vec<CheckpointSequenceNumber>> {
+        let mut oversized = vec![];
+        for (seq, checkpoint) in self.certified_checkpoints.range_iter(start..=end) {
+            let checkpoint: VerifiedCheckpoint = checkpoint.into();
+            let contents = self
+                .get_checkpoint_contents(&checkpoint.content_digest)?
+                .ok_or(UserInputError::CheckpointContentsNotFound(
+                    checkpoint.content_digest,
+                ))?;
+            let size = bcs::serialized_size(&contents)
+                .map_err(|e| SuiError::GenericStorageError(e.to_string()))?;
+            if size > threshold_bytes {
+                oversized.push(seq);
+            }
+        }
+        Ok(oversized)
+    }
+
+    /// Writes the certified checkpoints in `[start, end]` and their contents to `writer`, as a
+    /// sequence of BCS encoded `(CertifiedCheckpointSummary, CheckpointContents)` pairs, each
+    /// prefixed with its length as a little endian u64. Checkpoints missing from the store are
+    /// skipped, but a checkpoint whose contents are missing is an error. Returns the number of
+    /// checkpoints written. The output can be loaded back with `import_range`.
+    pub fn export_range(
+        &self,
+        start: CheckpointSequenceNumber,
+        end: CheckpointSequenceNumber,
+        mut writer: impl Write,
+    ) -> SuiResult<u64> {
+        let mut count = 0;
+        for (_, checkpoint) in self.certified_checkpoints.range_iter(start..=end) {
+            let checkpoint: VerifiedCheckpoint = checkpoint.into();
+            let contents = self
+                .get_checkpoint_contents(&checkpoint.content_digest)?
+                .ok_or(UserInputError::CheckpointContentsNotFound(
+                    checkpoint.content_digest,
+                ))?;
+            let bytes = bcs::to_bytes(&(checkpoint.inner(), &contents))
+                .map_err(|e| SuiError::GenericStorageError(e.to_string()))?;
+            writer
+                .write_all(&(bytes.len() as u64).to_le_bytes())
+                .and_then(|()| writer.write_all(&bytes))
+                .map_err(|e| SuiError::GenericStorageError(e.to_string()))?;
+            count += 1;
+        }
+        writer
+            .flush()
+            .map_err(|e| SuiError::GenericStorageError(e.to_string()))?;
+        Ok(count)
+    }
+
+    /// Reads checkpoints written by `export_range` from `reader` and inserts them along with
+    /// their contents, as if they had been verified by state sync. Each checkpoint is verified
+    /// against the committee of its epoch (see `committee_for_epoch`) and against its contents
+    /// before anything is inserted, so the checkpoints must be imported in order starting from an
+    /// epoch whose committee is known to the store. Returns the number of checkpoints imported.
+    pub fn import_range(&self, mut reader: impl Read) -> SuiResult<u64> {
+        let mut count = 0;
+        let mut committee: Option<Committee> = None;
+        loop {
+            let mut len_bytes = [0u8; 8];
+            match reader.read_exact(&mut len_bytes) {
+                Ok(()) => (),
+                Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => break,
+                Err(e) => return Err(SuiError::GenericStorageError(e.to_string())),
+            }
+            // The length comes from the file, so the buffer only grows with the bytes actually
+            // read instead of being allocated upfront.
+            let len = u64::from_le_bytes(len_bytes);
+            let mut bytes = Vec::new();
+            (&mut reader)
+                .take(len)
+                .read_to_end(&mut bytes)
+                .map_err(|e| SuiError::GenericStorageError(e.to_string()))?;
+            if bytes.len() as u64 != len {
+                return Err(SuiError::GenericStorageError(format!(
+                    "Imported checkpoint is truncated, expected {len} bytes but read {}",
+                    bytes.len()
+                )));
+            }
+            let (checkpoint, contents): (CertifiedCheckpointSummary, CheckpointContents) =
+                bcs::from_bytes(&bytes)
+                    .map_err(|e| SuiError::GenericStorageError(e.to_string()))?;
+            if committee.as_ref().map(|c| c.epoch) != Some(checkpoint.epoch) {
+                committee = Some(self.committee_for_epoch(checkpoint.epoch)?.ok_or_else(|| {
+                    SuiError::GenericStorageError(format!(
+                        "Committee of epoch {} is not available to verify imported checkpoint {}",
+                        checkpoint.epoch, checkpoint.sequence_number
+                    ))
+                })?);
+            }
+            checkpoint.verify_with_contents(committee.as_ref().unwrap(), Some(&contents))?;
+            let checkpoint = VerifiedCheckpoint::new_from_verified(checkpoint);
+            self.check_certified_checkpoint(&checkpoint)?;
+            self.insert_checkpoint_contents(contents)?;
+            self.insert_verified_checkpoint(&checkpoint)?;
+            count += 1;
+        }
+        Ok(count)
+    }
+
+    /// Creates a rocksdb checkpoint of the checkpoint store at `path`. The snapshot is taken at a
+    /// single point in time and contains every write batch committed before it, so each table is
+    /// internally consistent. There is no coordination with the checkpoint builder, aggregator or
+    /// state sync though: on a live node the snapshot may fall between two related writes, e.g.
+    /// a certified checkpoint whose contents or watermarks were not written yet. Consumers of a
+    /// snapshot taken on a live node should rely on the watermarks rather than on the highest
+    /// checkpoint present in the tables.
     pub fn checkpoint_db(&self, path: &Path) -> SuiResult {
         // This checkpoints the entire db and not one column family
         self.checkpoint_content
@@ -654,12 +2219,120 @@ impl CheckpointStore {
     }
 }
 
+/// Rejects checkpoint size limits of zero, with which the builder could not fit any transaction
+/// in a checkpoint.
+fn check_size_limits(
+    max_transactions_per_checkpoint: usize,
+    max_checkpoint_size_bytes: usize,
+) -> SuiResult {
+    if max_transactions_per_checkpoint == 0 || max_checkpoint_size_bytes == 0 {
+        return Err(SuiError::GenericAuthorityError {
+            error: format!(
+                "Invalid checkpoint size limits: max_transactions_per_checkpoint is {max_transactions_per_checkpoint} and max_checkpoint_size_bytes is {max_checkpoint_size_bytes}, both must be positive"
+            ),
+        });
+    }
+    Ok(())
+}
+
+/// Returns the delay before retrying after a checkpoint builder or aggregator error: 1s, plus a
+/// random jitter of up to `jitter_percent` percent of it.
+fn error_retry_delay(jitter_percent: u64) -> Duration {
+    let base = Duration::from_secs(1);
+    let max_jitter_ms = base.as_millis() as u64 * jitter_percent / 100;
+    base + Duration::from_millis(rand::thread_rng().gen_range(0..=max_jitter_ms))
+}
+
+fn assert_genesis_checkpoint(checkpoint: &VerifiedCheckpoint) {
+    assert_eq!(
+        checkpoint.epoch(),
+        0,
+        "can't insert a genesis checkpoint that is not in epoch 0"
+    );
+    assert_eq!(
+        *checkpoint.sequence_number(),
+        0,
+        "can't insert a genesis checkpoint that doesn't have a sequence number of 0"
+    );
+}
+
+/// Estimates the number of entries of `table` below `target_seq`, assuming the sequence numbers
+/// are contiguous from the lowest one stored, and bounded by the estimated number of keys.
+fn estimate_entries_below<V: Serialize + DeserializeOwned>(
+    table: &DBMap<CheckpointSequenceNumber, V>,
+    target_seq: CheckpointSequenceNumber,
+) -> SuiResult<u64> {
+    let Some(lowest) = table.keys().next().transpose()? else {
+        return Ok(0);
+    };
+    Ok(target_seq
+        .saturating_sub(lowest)
+        .min(int_property(table, rocksdb::properties::ESTIMATE_NUM_KEYS)?))
+}
+
+fn average_entry_size<K, V>(table: &DBMap<K, V>) -> SuiResult<u64> {
+    let num_keys = int_property(table, rocksdb::properties::ESTIMATE_NUM_KEYS)?;
+    if num_keys == 0 {
+        return Ok(0);
+    }
+    Ok(int_property(table, rocksdb::properties::ESTIMATE_LIVE_DATA_SIZE)? / num_keys)
+}
+
+fn int_property<K, V>(table: &DBMap<K, V>, name: &'static std::ffi::CStr) -> SuiResult<u64> {
+    Ok(table
+        .rocksdb
+        .property_int_value_cf(&table.cf(), name)
+        .map_err(|e| SuiError::GenericStorageError(e.into_string()))?
+        .unwrap_or_default())
+}
+
+fn zstd_db_options(mut db_options: DBOptions, level: i32) -> DBOptions {
+    db_options
+        .options
+        .set_compression_type(rocksdb::DBCompressionType::Zstd);
+    // Re-applied so that the per-level compression uses zstd on every level
+    db_options.options.set_min_level_to_compress(0);
+    // Arguments are window_bits, level, strategy and max_dict_bytes
+    db_options.options.set_compression_options(-14, level, 0, 0);
+    db_options
+}
+
+/// Gas cost incurred by `checkpoint` alone, derived from its epoch rolling gas cost summary and
+/// the one of the previous checkpoint.
+fn checkpoint_gas_cost(
+    previous: Option<&CheckpointSummary>,
+    checkpoint: &CheckpointSummary,
+) -> GasCostSummary {
+    let current = &checkpoint.epoch_rolling_gas_cost_summary;
+    match previous {
+        Some(previous) if previous.epoch == checkpoint.epoch => {
+            let previous = &previous.epoch_rolling_gas_cost_summary;
+            GasCostSummary::new(
+                current
+                    .computation_cost
+                    .saturating_sub(previous.computation_cost),
+                current.storage_cost.saturating_sub(previous.storage_cost),
+                current
+                    .storage_rebate
+                    .saturating_sub(previous.storage_rebate),
+                current
+                    .non_refundable_storage_fee
+                    .saturating_sub(previous.non_refundable_storage_fee),
+            )
+        }
+        // The rolling summary starts over with the first checkpoint of every epoch
+        _ => current.clone(),
+    }
+}
+
 #[derive(Copy, Clone, Debug, Serialize, Deserialize)]
 pub enum CheckpointWatermark {
     HighestVerified,
     HighestSynced,
     HighestExecuted,
     HighestPruned,
+    /// Highest checkpoint whose full contents were stored by `insert_verified_checkpoint_contents`
+    HighestWithContents,
 }
 
 pub struct CheckpointBuilder {
@@ -674,7 +2347,23 @@ pub struct CheckpointBuilder {
     exit: watch::Receiver<()>,
     metrics: Arc<CheckpointMetrics>,
     max_transactions_per_checkpoint: usize,
+    /// Taken from the protocol config by the caller when the service is spawned for the epoch,
+    /// so a protocol upgrade changes it at the epoch boundary and never within an epoch
     max_checkpoint_size_bytes: usize,
+    previous_epoch_checkpoint_lookup: PreviousEpochCheckpointLookup,
+    dead_letter_failed_outputs: bool,
+    error_retry_jitter_percent: u64,
+    deep_dependency_chain_warn_depth: usize,
+    persist_epoch_stats: bool,
+    record_build_failures: bool,
+    reject_timestamp_regression: bool,
+    validate_transaction_digests: bool,
+    on_checkpoint_constructed: CheckpointConstructedHook,
+    max_uncertified_checkpoints: Option<u64>,
+    /// Updated by the aggregator with the sequence number of every checkpoint it certifies
+    highest_certified: watch::Receiver<CheckpointSequenceNumber>,
+    memory_pressure_signal: MemoryPressureSignal,
+    max_effects_read_batch: Option<usize>,
 }
 
 pub struct CheckpointAggregator {
@@ -686,6 +2375,37 @@ pub struct CheckpointAggregator {
     output: Box<dyn CertifiedCheckpointOutput>,
     state: Arc<AuthorityState>,
     metrics: Arc<CheckpointMetrics>,
+    /// Updated with the sequence number of every checkpoint certified by this aggregator, and
+    /// after every aggregation round with the checkpoints certified through state sync
+    highest_certified: watch::Sender<CheckpointSequenceNumber>,
+    certified_events: broadcast::Sender<CertifiedCheckpointEvent>,
+    /// Updated after every aggregation round with the checkpoint being aggregated, if any, and
+    /// the stake of the signatures accumulated for it so far
+    pending_signature_stake: watch::Sender<Option<(CheckpointSequenceNumber, StakeUnit)>>,
+    aggregation_poll_interval: Duration,
+    error_retry_jitter_percent: u64,
+    split_brain_halt_hook: SplitBrainHaltHook,
+    split_brain_lookback: u64,
+    split_brain_peers_per_faction: usize,
+    max_retained_fork_dumps: usize,
+    split_brain_report: Arc<Mutex<Option<SplitBrainReport>>>,
+    certification_ceiling: Arc<Mutex<Option<CheckpointSequenceNumber>>>,
+    /// Set by `CheckpointService::force_reaggregate` to make the aggregator drop its current
+    /// signature aggregator and rebuild it from the pending signatures
+    reaggregate_request: Arc<Mutex<Option<CheckpointSequenceNumber>>>,
+    epoch_participation: Arc<Mutex<EpochParticipation>>,
+    record_checkpoint_provenance: bool,
+}
+
+/// Channels and state that the aggregator shares with the `CheckpointService` spawning it.
+struct CheckpointAggregatorShared {
+    highest_certified: watch::Sender<CheckpointSequenceNumber>,
+    certified_events: broadcast::Sender<CertifiedCheckpointEvent>,
+    pending_signature_stake: watch::Sender<Option<(CheckpointSequenceNumber, StakeUnit)>>,
+    split_brain_report: Arc<Mutex<Option<SplitBrainReport>>>,
+    certification_ceiling: Arc<Mutex<Option<CheckpointSequenceNumber>>>,
+    reaggregate_request: Arc<Mutex<Option<CheckpointSequenceNumber>>>,
+    epoch_participation: Arc<Mutex<EpochParticipation>>,
 }
 
 // This holds information to aggregate signatures for one checkpoint
@@ -698,6 +2418,16 @@ pub struct CheckpointSignatureAggregator {
     tables: Arc<CheckpointStore>,
     state: Arc<AuthorityState>,
     metrics: Arc<CheckpointMetrics>,
+    split_brain_halt_hook: SplitBrainHaltHook,
+    split_brain_lookback: u64,
+    split_brain_peers_per_faction: usize,
+    max_retained_fork_dumps: usize,
+    /// Updated with the latest split brain detected by this aggregator
+    split_brain_report: Arc<Mutex<Option<SplitBrainReport>>>,
+    /// When this aggregator was created, used to measure the certification latency
+    started: Instant,
+    /// Validators whose signature for the local digest was aggregated so far
+    signers: HashSet<AuthorityName>,
 }
 
 impl CheckpointBuilder {
@@ -714,6 +2444,8 @@ impl CheckpointBuilder {
         metrics: Arc<CheckpointMetrics>,
         max_transactions_per_checkpoint: usize,
         max_checkpoint_size_bytes: usize,
+        highest_certified: watch::Receiver<CheckpointSequenceNumber>,
+        config: &CheckpointServiceConfig,
     ) -> Self {
         Self {
             state,
@@ -728,6 +2460,19 @@ impl CheckpointBuilder {
             metrics,
             max_transactions_per_checkpoint,
             max_checkpoint_size_bytes,
+            previous_epoch_checkpoint_lookup: config.previous_epoch_checkpoint_lookup,
+            dead_letter_failed_outputs: config.dead_letter_failed_outputs,
+            error_retry_jitter_percent: config.error_retry_jitter_percent,
+            deep_dependency_chain_warn_depth: config.deep_dependency_chain_warn_depth,
+            persist_epoch_stats: config.persist_epoch_stats,
+            record_build_failures: config.record_build_failures,
+            reject_timestamp_regression: config.reject_timestamp_regression,
+            validate_transaction_digests: config.validate_transaction_digests,
+            on_checkpoint_constructed: config.on_checkpoint_constructed.clone(),
+            max_uncertified_checkpoints: config.max_uncertified_checkpoints,
+            highest_certified,
+            memory_pressure_signal: config.memory_pressure_signal.clone(),
+            max_effects_read_batch: config.max_effects_read_batch,
         }
     }
 
@@ -742,26 +2487,85 @@ impl CheckpointBuilder {
                 }
                 Ok(false) => (),
             };
+            if self.dead_letter_failed_outputs {
+                if let Err(e) = self.retry_failed_outputs().await {
+                    error!(
+                        "Error while retrying dead-lettered checkpoint outputs: {:?}",
+                        e
+                    );
+                }
+            }
             let mut last = self
                 .epoch_store
                 .last_built_checkpoint_commit_height()
                 .expect("epoch should not have ended");
+            if let Some(height) = last {
+                self.metrics.builder_last_commit_height.set(height as i64);
+            }
+            // Every pending checkpoint is built as soon as it is read, there is no minimum
+            // interval or coalescing between them. The only throttling is the memory pressure
+            // signal and the bound on uncertified checkpoints below, which must not apply to the
+            // pending checkpoint with `last_of_epoch` set, since delaying it would delay
+            // reconfiguration.
             for (height, pending) in self
                 .epoch_store
                 .get_pending_checkpoints(last)
                 .expect("unexpected epoch store error")
             {
                 last = Some(height);
+                if !pending.details.last_of_epoch && self.memory_pressure_signal.under_pressure() {
+                    debug!(
+                        checkpoint_commit_height = height,
+                        "Deferring checkpoint build under memory pressure"
+                    );
+                    if let Either::Left(_) = select(
+                        self.exit.changed().boxed(),
+                        self.memory_pressure_signal.released().boxed(),
+                    )
+                    .await
+                    {
+                        break 'main;
+                    }
+                }
+                while !pending.details.last_of_epoch && self.too_many_uncertified_checkpoints() {
+                    debug!(
+                        checkpoint_commit_height = height,
+                        "Deferring checkpoint build until more checkpoints are certified"
+                    );
+                    match select(
+                        self.exit.changed().boxed(),
+                        self.highest_certified.changed().boxed(),
+                    )
+                    .await
+                    {
+                        Either::Left(_) => break 'main,
+                        Either::Right((Ok(()), _)) => {}
+                        Either::Right((Err(_), exit)) => {
+                            // The aggregator only drops its sender when the service shuts
+                            // down, so no more checkpoints will be certified.
+                            let _ = exit.await;
+                            break 'main;
+                        }
+                    }
+                }
                 debug!(
                     checkpoint_commit_height = height,
                     "Making checkpoint at commit height"
                 );
                 if let Err(e) = self.make_checkpoint(height, pending).await {
-                    error!("Error while making checkpoint, will retry in 1s: {:?}", e);
-                    tokio::time::sleep(Duration::from_secs(1)).await;
+                    let delay = error_retry_delay(self.error_retry_jitter_percent);
+                    error!(
+                        "Error while making checkpoint, will retry in {:?}: {:?}",
+                        delay, e
+                    );
+                    if self.record_build_failures {
+                        self.record_build_failure(height, &e);
+                    }
+                    tokio::time::sleep(delay).await;
                     self.metrics.checkpoint_errors.inc();
                     continue 'main;
                 }
+                self.metrics.builder_last_commit_height.set(height as i64);
             }
             debug!("Waiting for more checkpoints from consensus after processing {last:?}");
             match select(self.exit.changed().boxed(), self.notify.notified().boxed()).await {
@@ -775,31 +2579,135 @@ impl CheckpointBuilder {
         info!("Shutting down CheckpointBuilder");
     }
 
+    /// Returns whether the number of checkpoints built but not certified yet reached
+    /// `max_uncertified_checkpoints`.
+    fn too_many_uncertified_checkpoints(&self) -> bool {
+        let Some(max_uncertified_checkpoints) = self.max_uncertified_checkpoints else {
+            return false;
+        };
+        let Some((last_built, _)) = self
+            .epoch_store
+            .last_built_checkpoint_summary()
+            .expect("unexpected epoch store error")
+        else {
+            return false;
+        };
+        let next_to_certify = self
+            .tables
+            .get_latest_certified_checkpoint()
+            .map(|checkpoint| *checkpoint.sequence_number() + 1)
+            .unwrap_or_default();
+        (last_built + 1).saturating_sub(next_to_certify) >= max_uncertified_checkpoints
+    }
+
+    fn record_build_failure(&self, commit_height: CheckpointCommitHeight, error: &anyhow::Error) {
+        let failure = BuildFailure {
+            commit_height,
+            error: format!("{:?}", error),
+            timestamp_ms: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_millis() as u64,
+        };
+        if let Err(e) = self.tables.record_build_failure(&failure) {
+            warn!("Failed to record checkpoint build failure: {:?}", e);
+        }
+    }
+
     #[instrument(level = "debug", skip_all)]
     async fn make_checkpoint(
         &self,
         height: CheckpointCommitHeight,
         pending: PendingCheckpoint,
     ) -> anyhow::Result<()> {
+        let build_duration = &self.metrics.checkpoint_build_duration_seconds;
         self.metrics
             .checkpoint_roots_count
             .inc_by(pending.roots.len() as u64);
+        let notify_read_timer = build_duration
+            .with_label_values(&["notify_read"])
+            .start_timer();
         let roots = self
-            .effects_store
-            .notify_read_executed_effects(pending.roots)
+            .read_root_effects(pending.roots)
             .in_monitored_scope("CheckpointNotifyRead")
             .await?;
+        notify_read_timer.observe_duration();
         let _scope = monitored_scope("CheckpointBuilder");
-        let unsorted = self.complete_checkpoint_effects(roots)?;
+        let unsorted = {
+            let _timer = build_duration
+                .with_label_values(&["complete_effects"])
+                .start_timer();
+            self.complete_checkpoint_effects(height, roots)?
+        };
         let sorted = {
-            let _scope = monitored_scope("CheckpointBuilder::causal_sort");
-            CausalOrder::causal_sort(unsorted)
+            let _timer = build_duration
+                .with_label_values(&["causal_sort"])
+                .start_timer();
+            self.causal_sort_effects(unsorted)
         };
-        let new_checkpoint = self.create_checkpoints(sorted, pending.details).await?;
+        let new_checkpoint = {
+            let _timer = build_duration.with_label_values(&["create"]).start_timer();
+            self.create_checkpoints(sorted, pending.details).await?
+        };
+        let _timer = build_duration.with_label_values(&["write"]).start_timer();
         self.write_checkpoints(height, new_checkpoint).await?;
         Ok(())
     }
 
+    /// Waits for and reads the effects of `roots`, in batches of at most `max_effects_read_batch`
+    /// transactions if it is set.
+    async fn read_root_effects(
+        &self,
+        roots: Vec<TransactionDigest>,
+    ) -> SuiResult<Vec<TransactionEffects>> {
+        let batch_size = match self.max_effects_read_batch {
+            Some(batch_size) if roots.len() > batch_size => batch_size.max(1),
+            _ => return self.effects_store.notify_read_executed_effects(roots).await,
+        };
+        let mut effects = Vec::with_capacity(roots.len());
+        for batch in roots.chunks(batch_size) {
+            effects.extend(
+                self.effects_store
+                    .notify_read_executed_effects(batch.to_vec())
+                    .await?,
+            );
+        }
+        Ok(effects)
+    }
+
+    /// Returns the checkpoints that would be built from `roots`, without writing or outputting
+    /// them. The checkpoints are never treated as closing the epoch, so the epoch change
+    /// transaction is not executed and the last checkpoint has no end of epoch data.
+    pub async fn build_preview(
+        &self,
+        roots: Vec<TransactionDigest>,
+        details: PendingCheckpointInfo,
+    ) -> SuiResult<Vec<(CheckpointSummary, CheckpointContents)>> {
+        let roots = self.read_root_effects(roots).await?;
+        let unsorted = self.complete_checkpoint_effects(details.commit_height, roots)?;
+        let sorted = self.causal_sort_effects(unsorted);
+        let details = PendingCheckpointInfo {
+            last_of_epoch: false,
+            ..details
+        };
+        self.create_checkpoints(sorted, details).await.map_err(|e| {
+            e.downcast::<SuiError>()
+                .unwrap_or_else(|e| SuiError::GenericAuthorityError {
+                    error: format!("{e:?}"),
+                })
+        })
+    }
+
+    /// Orders the effects of a checkpoint so that every transaction comes after all of its
+    /// dependencies included in the checkpoint.
+    pub(crate) fn causal_sort_effects(
+        &self,
+        effects: Vec<TransactionEffects>,
+    ) -> Vec<TransactionEffects> {
+        let _scope = monitored_scope("CheckpointBuilder::causal_sort");
+        CausalOrder::causal_sort(effects)
+    }
+
     #[instrument(level = "debug", skip_all)]
     async fn write_checkpoints(
         &self,
@@ -814,14 +2722,33 @@ impl CheckpointBuilder {
                 checkpoint_seq = summary.sequence_number,
                 "Created checkpoint",
             );
-            self.output
+            let sequence_number = summary.sequence_number;
+            if let Err(e) = self
+                .output
                 .checkpoint_created(summary, contents, &self.epoch_store)
-                .await?;
+                .await
+            {
+                if !self.dead_letter_failed_outputs {
+                    return Err(e);
+                }
+                warn!(
+                    checkpoint_seq = sequence_number,
+                    "Checkpoint output failed, dead-lettering it for retry: {:?}", e
+                );
+                self.metrics.dead_lettered_checkpoint_outputs.inc();
+                batch.insert_batch(&self.tables.failed_outputs, [(sequence_number, ())])?;
+            }
+            self.on_checkpoint_constructed
+                .checkpoint_constructed(summary, contents);
 
             self.metrics
                 .transactions_included_in_checkpoint
                 .inc_by(contents.size() as u64);
-            let sequence_number = summary.sequence_number;
+            let contents_bytes = bcs::serialized_size(contents)
+                .map_err(|e| SuiError::GenericStorageError(e.to_string()))?;
+            self.metrics
+                .checkpoint_content_bytes
+                .report(contents_bytes as u64);
             self.metrics
                 .last_constructed_checkpoint
                 .set(sequence_number as i64);
@@ -855,6 +2782,62 @@ impl CheckpointBuilder {
         Ok(())
     }
 
+    /// Re-sends the output of every dead-lettered checkpoint, removing it from the dead-letter
+    /// table once the output succeeds. Entries that belong to another epoch, or whose local
+    /// summary or contents are no longer available, are dropped since they can't be re-sent.
+    async fn retry_failed_outputs(&self) -> SuiResult {
+        let failed = self
+            .tables
+            .failed_outputs
+            .keys()
+            .collect::<Result<Vec<_>, _>>()?;
+        for sequence_number in failed {
+            let summary = self
+                .tables
+                .locally_computed_checkpoints
+                .get(&sequence_number)?;
+            let contents = match &summary {
+                Some(summary) => self
+                    .tables
+                    .get_checkpoint_contents(&summary.content_digest)?,
+                None => None,
+            };
+            let (summary, contents) = match (summary, contents) {
+                (Some(summary), Some(contents)) if summary.epoch == self.epoch_store.epoch() => {
+                    (summary, contents)
+                }
+                _ => {
+                    warn!(
+                        checkpoint_seq = sequence_number,
+                        "Dropping dead-lettered checkpoint output that can no longer be retried"
+                    );
+                    self.tables.failed_outputs.remove(&sequence_number)?;
+                    continue;
+                }
+            };
+            match self
+                .output
+                .checkpoint_created(&summary, &contents, &self.epoch_store)
+                .await
+            {
+                Ok(()) => {
+                    debug!(
+                        checkpoint_seq = sequence_number,
+                        "Retried dead-lettered checkpoint output"
+                    );
+                    self.tables.failed_outputs.remove(&sequence_number)?;
+                }
+                Err(e) => {
+                    debug!(
+                        checkpoint_seq = sequence_number,
+                        "Dead-lettered checkpoint output failed again: {:?}", e
+                    );
+                }
+            }
+        }
+        Ok(())
+    }
+
     #[allow(clippy::type_complexity)]
     fn split_checkpoint_chunks(
         &self,
@@ -920,10 +2903,26 @@ impl CheckpointBuilder {
             let epoch = self.epoch_store.epoch();
             if epoch > 0 {
                 let previous_epoch = epoch - 1;
-                let last_verified = self.tables.get_epoch_last_checkpoint(previous_epoch)?;
+                let mut last_verified = self.tables.get_epoch_last_checkpoint(previous_epoch)?;
+                if last_verified.is_none()
+                    && self.previous_epoch_checkpoint_lookup
+                        == PreviousEpochCheckpointLookup::SnapshotRecovery
+                {
+                    warn!("Last checkpoint of previous epoch {previous_epoch} is not in the epoch boundary map, searching certified checkpoints");
+                    last_verified = self
+                        .tables
+                        .find_epoch_last_certified_checkpoint(previous_epoch)?;
+                }
                 last_checkpoint = last_verified.map(VerifiedCheckpoint::into_summary_and_sequence);
                 if let Some((ref seq, _)) = last_checkpoint {
                     debug!("No checkpoints in builder DB, taking checkpoint from previous epoch with sequence {seq}");
+                } else if self.previous_epoch_checkpoint_lookup
+                    == PreviousEpochCheckpointLookup::SnapshotRecovery
+                {
+                    // The boundary checkpoint may not have been synced yet, retry later
+                    anyhow::bail!(
+                        "Can not find last checkpoint for previous epoch {previous_epoch}"
+                    );
                 } else {
                     // This is some serious bug with when CheckpointBuilder started so surfacing it via panic
                     panic!("Can not find last checkpoint for previous epoch {previous_epoch}");
@@ -960,8 +2959,19 @@ impl CheckpointBuilder {
                 .into_iter()
                 .zip(transactions_and_sizes.into_iter())
             {
-                let (transaction, size) = transaction_and_size
-                    .unwrap_or_else(|| panic!("Could not find executed transaction {:?}", effects));
+                let (transaction, size) =
+                    transaction_and_size.ok_or(SuiError::CheckpointTransactionNotFound {
+                        digest: *effects.transaction_digest(),
+                    })?;
+                if self.validate_transaction_digests
+                    && transaction.digest() != effects.transaction_digest()
+                {
+                    anyhow::bail!(
+                        "Transaction {:?} read for checkpoint does not match effects of transaction {:?}",
+                        transaction.digest(),
+                        effects.transaction_digest()
+                    );
+                }
                 // ConsensusCommitPrologue and AuthenticatorStateUpdate are guaranteed to be
                 // processed before we reach here
                 if !matches!(
@@ -994,6 +3004,7 @@ impl CheckpointBuilder {
 
         let chunks = self.split_checkpoint_chunks(all_effects_and_transaction_sizes, signatures)?;
         let chunks_count = chunks.len();
+        self.metrics.chunks_per_commit.report(chunks_count as u64);
 
         let mut checkpoints = Vec::with_capacity(chunks_count);
         debug!(
@@ -1023,6 +3034,12 @@ impl CheckpointBuilder {
                 if last_checkpoint.timestamp_ms > timestamp_ms {
                     error!("Unexpected decrease of checkpoint timestamp, sequence: {}, previous: {}, current: {}",
                     sequence_number,  last_checkpoint.timestamp_ms, timestamp_ms);
+                    if self.reject_timestamp_regression {
+                        anyhow::bail!(
+                            "Checkpoint {sequence_number} timestamp {timestamp_ms} is lower than previous checkpoint timestamp {}",
+                            last_checkpoint.timestamp_ms
+                        );
+                    }
                 }
             }
 
@@ -1110,6 +3127,9 @@ impl CheckpointBuilder {
                     "creating last checkpoint of epoch {}", epoch
                 );
                 if let Some(stats) = self.tables.get_epoch_stats(epoch, &summary) {
+                    if self.persist_epoch_stats {
+                        self.tables.insert_epoch_stats(epoch, &stats)?;
+                    }
                     self.epoch_store
                         .report_epoch_metrics_at_last_checkpoint(stats);
                 }
@@ -1173,11 +3193,13 @@ impl CheckpointBuilder {
     #[instrument(level = "debug", skip_all)]
     fn complete_checkpoint_effects(
         &self,
+        height: CheckpointCommitHeight,
         mut roots: Vec<TransactionEffects>,
     ) -> SuiResult<Vec<TransactionEffects>> {
         let _scope = monitored_scope("CheckpointBuilder::complete_checkpoint_effects");
         let mut results = vec![];
         let mut seen = HashSet::new();
+        let mut depth = 0;
         loop {
             let mut pending = HashSet::new();
 
@@ -1192,6 +3214,21 @@ impl CheckpointBuilder {
                 // Unnecessary to read effects of a dependency if the effect is already processed.
                 seen.insert(*digest);
 
+                // Effects executed in a future epoch can only come from a corrupted store.
+                if effect.executed_epoch() > self.epoch_store.epoch() {
+                    self.metrics.checkpoint_future_epoch_effects.inc();
+                    error!(
+                        tx_digest = ?digest,
+                        executed_epoch = effect.executed_epoch(),
+                        current_epoch = self.epoch_store.epoch(),
+                        "Checkpoint dependency was executed in a future epoch",
+                    );
+                    return Err(SuiError::GenericStorageError(format!(
+                        "Effects of transaction {digest:?} were executed in future epoch {}",
+                        effect.executed_epoch()
+                    )));
+                }
+
                 // Skip roots already included in checkpoints or roots from previous epochs
                 if tx_included || effect.executed_epoch() < self.epoch_store.epoch() {
                     continue;
@@ -1220,6 +3257,7 @@ impl CheckpointBuilder {
             if pending.is_empty() {
                 break;
             }
+            depth += 1;
             let pending = pending.into_iter().collect::<Vec<_>>();
             let effects = self.effects_store.multi_get_executed_effects(&pending)?;
             let effects = effects
@@ -1235,6 +3273,13 @@ impl CheckpointBuilder {
                 .collect::<Vec<_>>();
             roots = effects;
         }
+        if depth >= self.deep_dependency_chain_warn_depth {
+            warn!(
+                checkpoint_commit_height = height,
+                depth,
+                "deep_dependency_chain: checkpoint dependencies are nested {depth} levels deep",
+            );
+        }
         Ok(results)
     }
 }
@@ -1248,8 +3293,19 @@ impl CheckpointAggregator {
         output: Box<dyn CertifiedCheckpointOutput>,
         state: Arc<AuthorityState>,
         metrics: Arc<CheckpointMetrics>,
+        shared: CheckpointAggregatorShared,
+        config: &CheckpointServiceConfig,
     ) -> Self {
         let current = None;
+        let CheckpointAggregatorShared {
+            highest_certified,
+            certified_events,
+            pending_signature_stake,
+            split_brain_report,
+            certification_ceiling,
+            reaggregate_request,
+            epoch_participation,
+        } = shared;
         Self {
             tables,
             epoch_store,
@@ -1259,6 +3315,20 @@ impl CheckpointAggregator {
             output,
             state,
             metrics,
+            highest_certified,
+            certified_events,
+            pending_signature_stake,
+            aggregation_poll_interval: config.aggregation_poll_interval,
+            error_retry_jitter_percent: config.error_retry_jitter_percent,
+            split_brain_halt_hook: config.split_brain_halt_hook.clone(),
+            split_brain_lookback: config.split_brain_lookback,
+            split_brain_peers_per_faction: config.split_brain_peers_per_faction,
+            max_retained_fork_dumps: config.max_retained_fork_dumps,
+            split_brain_report,
+            certification_ceiling,
+            reaggregate_request,
+            epoch_participation,
+            record_checkpoint_provenance: config.record_checkpoint_provenance,
         }
     }
 
@@ -1266,18 +3336,19 @@ impl CheckpointAggregator {
         info!("Starting CheckpointAggregator");
         loop {
             if let Err(e) = self.run_and_notify().await {
+                let delay = error_retry_delay(self.error_retry_jitter_percent);
                 error!(
-                    "Error while aggregating checkpoint, will retry in 1s: {:?}",
-                    e
+                    "Error while aggregating checkpoint, will retry in {:?}: {:?}",
+                    delay, e
                 );
                 self.metrics.checkpoint_errors.inc();
-                tokio::time::sleep(Duration::from_secs(1)).await;
+                tokio::time::sleep(delay).await;
                 continue;
             }
 
             match select(
                 self.exit.changed().boxed(),
-                timeout(Duration::from_secs(1), self.notify.notified()).boxed(),
+                timeout(self.aggregation_poll_interval, self.notify.notified()).boxed(),
             )
             .await
             {
@@ -1293,8 +3364,28 @@ impl CheckpointAggregator {
 
     async fn run_and_notify(&mut self) -> SuiResult {
         let summaries = self.run_inner()?;
-        for summary in summaries {
-            self.output.certified_checkpoint_created(&summary).await?;
+        // Checkpoints certified through state sync are not aggregated here, so they are only
+        // picked up from the store, for the builder waiting on uncertified checkpoints.
+        if let Some(highest) = self.next_checkpoint_to_certify().checked_sub(1) {
+            self.highest_certified.send_if_modified(|current| {
+                let advanced = highest > *current;
+                if advanced {
+                    *current = highest;
+                }
+                advanced
+            });
+        }
+        self.pending_signature_stake
+            .send_replace(self.current.as_ref().map(|current| {
+                (
+                    current.summary.sequence_number,
+                    current.signatures_by_digest.total_votes(),
+                )
+            }));
+        if !summaries.is_empty() {
+            self.output
+                .certified_checkpoints_created(&summaries)
+                .await?;
         }
         Ok(())
     }
@@ -1302,8 +3393,43 @@ impl CheckpointAggregator {
     fn run_inner(&mut self) -> SuiResult<Vec<CertifiedCheckpointSummary>> {
         let _scope = monitored_scope("CheckpointAggregator");
         let mut result = vec![];
+        let reaggregate_request = self.reaggregate_request.lock().take();
+        if let Some(seq) = reaggregate_request {
+            let next_to_certify = self.next_checkpoint_to_certify();
+            let current_seq = self
+                .current
+                .as_ref()
+                .map(|current| current.summary.sequence_number);
+            // The checkpoint may have been certified since the request was made, in which case
+            // the request is dropped.
+            if seq > next_to_certify {
+                // Checkpoints are certified in order, so the aggregator can only be rebuilt for
+                // `seq` once it is the next checkpoint to certify. Keep the request unless a newer
+                // one was made in the meantime.
+                self.reaggregate_request.lock().get_or_insert(seq);
+            } else if seq == next_to_certify && current_seq != Some(seq) {
+                info!(
+                    checkpoint_seq = seq,
+                    ?current_seq,
+                    "Rebuilding signature aggregator from pending signatures"
+                );
+                // The loop below rebuilds the aggregator for the next checkpoint to certify,
+                // which is `seq`.
+                self.current = None;
+            }
+        }
         'outer: loop {
             let next_to_certify = self.next_checkpoint_to_certify();
+            let ceiling = *self.certification_ceiling.lock();
+            if let Some(ceiling) = ceiling {
+                if next_to_certify > ceiling {
+                    debug!(
+                        next_to_certify,
+                        ceiling, "Certification is frozen at the certification ceiling"
+                    );
+                    return Ok(result);
+                }
+            }
             let current = if let Some(current) = &mut self.current {
                 // It's possible that the checkpoint was already certified by
                 // the rest of the network and we've already received the
@@ -1332,6 +3458,13 @@ impl CheckpointAggregator {
                     tables: self.tables.clone(),
                     state: self.state.clone(),
                     metrics: self.metrics.clone(),
+                    split_brain_halt_hook: self.split_brain_halt_hook.clone(),
+                    split_brain_lookback: self.split_brain_lookback,
+                    split_brain_peers_per_faction: self.split_brain_peers_per_faction,
+                    max_retained_fork_dumps: self.max_retained_fork_dumps,
+                    split_brain_report: self.split_brain_report.clone(),
+                    started: Instant::now(),
+                    signers: HashSet::new(),
                 });
                 self.current.as_mut().unwrap()
             };
@@ -1374,13 +3507,32 @@ impl CheckpointAggregator {
                         ),
                     );
 
-                    self.tables.insert_certified_checkpoint(&summary)?;
+                    self.tables.check_certified_checkpoint(&summary)?;
+                    self.tables.insert_certified_checkpoint_with_provenance(
+                        &summary,
+                        self.record_checkpoint_provenance
+                            .then_some(CheckpointProvenance::LocalAggregation),
+                    )?;
+                    self.highest_certified
+                        .send_replace(current.summary.sequence_number);
+                    // Sending only fails if there are no subscribers.
+                    let _ = self.certified_events.send(CertifiedCheckpointEvent {
+                        seq: current.summary.sequence_number,
+                        digest: current.digest,
+                        epoch: current.summary.epoch,
+                        timestamp_ms: current.summary.timestamp_ms,
+                        num_txns: self.certified_transaction_count(&current.summary),
+                    });
                     self.metrics
                         .last_certified_checkpoint
                         .set(current.summary.sequence_number as i64);
                     current
                         .summary
                         .report_checkpoint_age_ms(&self.metrics.last_certified_checkpoint_age_ms);
+                    self.epoch_participation.lock().record(&current.signers);
+                    if current.summary.end_of_epoch_data.is_some() {
+                        self.log_epoch_participation();
+                    }
                     result.push(summary.into_inner());
                     self.current = None;
                     continue 'outer;
@@ -1393,6 +3545,57 @@ impl CheckpointAggregator {
         Ok(result)
     }
 
+    /// Returns the number of transactions in `summary`, from the network total transactions of
+    /// the previous checkpoint, which is certified since certification is sequential. This is
+    /// only used for notifications, so it returns 0 rather than failing if the previous
+    /// checkpoint cannot be read.
+    fn certified_transaction_count(&self, summary: &CheckpointSummary) -> u64 {
+        let Some(previous_seq) = summary.sequence_number.checked_sub(1) else {
+            return summary.network_total_transactions;
+        };
+        match self.tables.get_checkpoint_by_sequence_number(previous_seq) {
+            Ok(Some(previous)) => summary
+                .network_total_transactions
+                .saturating_sub(previous.network_total_transactions),
+            result => {
+                warn!(
+                    checkpoint_seq = summary.sequence_number,
+                    "Failed to read the previous certified checkpoint to count transactions: {:?}",
+                    result.err()
+                );
+                0
+            }
+        }
+    }
+
+    /// Logs the participation of each validator in the certification of the checkpoints of the
+    /// epoch, along with the overall stake weighted participation.
+    fn log_epoch_participation(&self) {
+        let committee = self.epoch_store.committee();
+        let participation = self.epoch_participation.lock();
+        let fractions = participation.fractions(committee);
+        let weighted: f64 = committee
+            .members()
+            .map(|(name, stake)| fractions[name] * *stake as f64)
+            .sum::<f64>()
+            / committee.total_votes() as f64;
+        info!(
+            epoch = self.epoch_store.epoch(),
+            certified_checkpoints = participation.certified_checkpoints,
+            "Stake weighted certification participation: {:.3}",
+            weighted
+        );
+        for (name, stake) in committee.members() {
+            info!(
+                epoch = self.epoch_store.epoch(),
+                "Validator {:?} with stake {} participated in certifying {:.3} of the checkpoints",
+                name.concise(),
+                stake,
+                fractions[name]
+            );
+        }
+    }
+
     fn next_checkpoint_to_certify(&self) -> CheckpointSequenceNumber {
         self.tables
             .certified_checkpoints
@@ -1411,11 +3614,14 @@ impl CheckpointSignatureAggregator {
         data: CheckpointSignatureMessage,
     ) -> Result<AuthorityStrongQuorumSignInfo, ()> {
         let their_digest = *data.summary.digest();
-        let (_, signature) = data.summary.into_data_and_sig();
+        let (their_summary, signature) = data.summary.into_data_and_sig();
         let author = signature.authority;
-        let envelope =
-            SignedCheckpointSummary::new_from_data_and_sig(self.summary.clone(), signature);
-        match self.signatures_by_digest.insert(their_digest, envelope) {
+        // Signatures are verified against the summary of their own digest, which differs from
+        // the local one for the signatures of other validators that forked.
+        match self
+            .signatures_by_digest
+            .insert_signature(their_digest, &their_summary, signature)
+        {
             InsertResult::Failed { error } => {
                 warn!(
                     checkpoint_seq = self.summary.sequence_number,
@@ -1423,7 +3629,16 @@ impl CheckpointSignatureAggregator {
                     author.concise(),
                     error
                 );
-                self.check_for_split_brain();
+                match error {
+                    // A bad signature from a single validator does not say anything
+                    // about disagreement between validators, just count it.
+                    SuiError::StakeAggregatorRepeatedSigner { .. }
+                    | SuiError::InvalidAuthenticator
+                    | SuiError::WrongEpoch { .. } => {
+                        self.metrics.rejected_checkpoint_signatures.inc();
+                    }
+                    _ => self.check_for_split_brain(),
+                }
                 Err(())
             }
             InsertResult::QuorumReached(cert) => {
@@ -1440,12 +3655,19 @@ impl CheckpointSignatureAggregator {
                     );
                     return Err(());
                 }
+                self.signers.insert(author);
+                self.metrics
+                    .checkpoint_certification_latency_seconds
+                    .observe(self.started.elapsed().as_secs_f64());
                 Ok(cert)
             }
             InsertResult::NotEnoughVotes {
                 bad_votes: _,
                 bad_authorities: _,
             } => {
+                if their_digest == self.digest {
+                    self.signers.insert(author);
+                }
                 self.check_for_split_brain();
                 Err(())
             }
@@ -1461,11 +3683,6 @@ impl CheckpointSignatureAggregator {
             "Checking for split brain condition"
         );
         if self.signatures_by_digest.quorum_unreachable() {
-            // TODO: at this point we should immediately halt processing
-            // of new transaction certificates to avoid building on top of
-            // forked output
-            // self.halt_all_execution();
-
             let digests_by_stake_messages = self
                 .signatures_by_digest
                 .get_all_unique_values()
@@ -1482,48 +3699,201 @@ impl CheckpointSignatureAggregator {
                 digests_by_stake_messages,
             );
             self.metrics.split_brain_checkpoint_forks.inc();
+            self.split_brain_halt_hook.halt();
+
+            *self.split_brain_report.lock() = Some(SplitBrainReport {
+                checkpoint_seq: self.summary.sequence_number,
+                local_digest: self.digest,
+                factions: self
+                    .signatures_by_digest
+                    .get_all_unique_values()
+                    .into_iter()
+                    .sorted_by_key(|(_, (_, stake))| -(*stake as i64))
+                    .map(|(digest, (authorities, stake))| (digest, authorities, stake))
+                    .collect(),
+            });
 
             let all_unique_values = self.signatures_by_digest.get_all_unique_values();
             let local_summary = self.summary.clone();
             let state = self.state.clone();
             let tables = self.tables.clone();
+            let lookback = self.split_brain_lookback;
+            let peers_per_faction = self.split_brain_peers_per_faction;
+            let max_retained_fork_dumps = self.max_retained_fork_dumps;
 
             tokio::spawn(async move {
-                diagnose_split_brain(all_unique_values, local_summary, state, tables).await;
+                diagnose_split_brain(
+                    all_unique_values,
+                    local_summary,
+                    state,
+                    tables,
+                    lookback,
+                    peers_per_faction,
+                    max_retained_fork_dumps,
+                )
+                .await;
             });
         }
     }
 }
 
+/// Machine readable summary written as the first line of a checkpoint fork dump.
+#[derive(Serialize)]
+struct ForkDumpHeader {
+    checkpoint_seq: CheckpointSequenceNumber,
+    epoch: EpochId,
+    local_digest: CheckpointDigest,
+    /// The disagreeing validators queried for each digest that differs from the local one
+    others: Vec<(AuthorityName, CheckpointDigest)>,
+    version: &'static str,
+}
+
+/// The debug representations of one aspect of two forked checkpoints.
+#[derive(Clone, Debug)]
+pub struct ForkDiff {
+    pub local: String,
+    pub other: String,
+}
+
+impl ForkDiff {
+    fn new(local: String, other: String) -> Self {
+        Self { local, other }
+    }
+
+    /// Returns the patch turning the local representation into the other one.
+    pub fn patch(&self) -> Patch<'_, str> {
+        create_patch(&self.local, &self.other)
+    }
+
+    pub fn differs(&self) -> bool {
+        self.local != self.other
+    }
+}
+
+/// Differences between the local version of a forked checkpoint and the version of another
+/// validator, as written in checkpoint fork dumps.
+#[derive(Clone, Debug)]
+pub struct ForkReport {
+    pub summary: ForkDiff,
+    pub contents: ForkDiff,
+    /// The transaction digests of both checkpoints, in order
+    pub transactions: ForkDiff,
+    /// The effects digests of both checkpoints, in order
+    pub effects: ForkDiff,
+}
+
+impl ForkReport {
+    /// Returns the patches of the report in the text format of checkpoint fork dumps.
+    pub fn text(&self) -> String {
+        format!(
+            "Summary Diff: \n{}\n\n\
+            Contents Diff: \n{}\n\n\
+            Transactions Diff: \n{}\n\n\
+            Effects Diff: \n{}",
+            self.summary.patch(),
+            self.contents.patch(),
+            self.transactions.patch(),
+            self.effects.patch(),
+        )
+    }
+}
+
+/// Compares the local version of a forked checkpoint with the version of another validator.
+pub fn build_fork_report(
+    local: &CheckpointSummary,
+    local_contents: &CheckpointContents,
+    other: &CheckpointSummary,
+    other_contents: &CheckpointContents,
+) -> ForkReport {
+    let (local_transactions, local_effects): (Vec<_>, Vec<_>) = local_contents
+        .enumerate_transactions(local)
+        .map(|(_, exec_digest)| (exec_digest.transaction, exec_digest.effects))
+        .unzip();
+    let (other_transactions, other_effects): (Vec<_>, Vec<_>) = other_contents
+        .enumerate_transactions(other)
+        .map(|(_, exec_digest)| (exec_digest.transaction, exec_digest.effects))
+        .unzip();
+    ForkReport {
+        summary: ForkDiff::new(format!("{local:?}"), format!("{other:?}")),
+        contents: ForkDiff::new(format!("{local_contents:?}"), format!("{other_contents:?}")),
+        transactions: ForkDiff::new(
+            format!("{local_transactions:#?}"),
+            format!("{other_transactions:#?}"),
+        ),
+        effects: ForkDiff::new(format!("{local_effects:#?}"), format!("{other_effects:#?}")),
+    }
+}
+
+/// Writes a fork dump for checkpoint `seq` in `dir`, then deletes the oldest dumps of `dir` so
+/// that at most `max_retained` of them are kept, the new one included.
+fn write_fork_dump(
+    dir: &Path,
+    seq: CheckpointSequenceNumber,
+    time: &DateTime<Utc>,
+    text: &str,
+    max_retained: usize,
+) -> std::io::Result<PathBuf> {
+    std::fs::create_dir_all(dir)?;
+    // The timestamp comes first so that dumps sort by creation time.
+    let path = dir.join(format!(
+        "{FORK_DUMP_FILE_PREFIX}{}_{seq}.txt",
+        time.format("%Y%m%dT%H%M%S%.6f")
+    ));
+    let mut file = File::create(&path)?;
+    write!(file, "{}", text)?;
+
+    let mut dumps = std::fs::read_dir(dir)?
+        .filter_map(|entry| {
+            let name = entry.ok()?.file_name().into_string().ok()?;
+            name.starts_with(FORK_DUMP_FILE_PREFIX).then_some(name)
+        })
+        .collect::<Vec<_>>();
+    dumps.sort();
+    let excess = dumps.len().saturating_sub(max_retained.max(1));
+    for name in &dumps[..excess] {
+        if let Err(e) = std::fs::remove_file(dir.join(name)) {
+            warn!("Failed to delete old checkpoint fork dump {name}: {:?}", e);
+        }
+    }
+    Ok(path)
+}
+
 /// Create data dump containing relevant data for diagnosing cause of the
-/// split brain by querying one disagreeing validator for full checkpoint contents.
-/// To minimize peer chatter, we only query one validator at random from each
-/// disagreeing faction, as all honest validators that participated in this round may
-/// inevitably run the same process.
+/// split brain by querying disagreeing validators for full checkpoint contents.
+/// To minimize peer chatter, we only query `peers_per_faction` validators at random from
+/// each disagreeing faction, as all honest validators that participated in this round may
+/// inevitably run the same process. The dump covers one responding validator per faction,
+/// and factions where no queried validator responded are left out.
 async fn diagnose_split_brain(
     all_unique_values: BTreeMap<CheckpointDigest, (Vec<AuthorityName>, StakeUnit)>,
     local_summary: CheckpointSummary,
     state: Arc<AuthorityState>,
     tables: Arc<CheckpointStore>,
+    lookback: u64,
+    peers_per_faction: usize,
+    max_retained_fork_dumps: usize,
 ) {
     debug!(
         checkpoint_seq = local_summary.sequence_number,
         "Running split brain diagnostics..."
     );
     let time = Utc::now();
-    // collect one random disagreeing validator per differing digest
-    let digest_to_validator = all_unique_values
+    // collect `peers_per_faction` random disagreeing validators per differing digest
+    let digest_to_validators = all_unique_values
         .iter()
         .filter_map(|(digest, (validators, _))| {
             if *digest != local_summary.digest() {
-                let random_validator = validators.choose(&mut OsRng).unwrap();
-                Some((*digest, *random_validator))
+                let random_validators = validators
+                    .choose_multiple(&mut OsRng, peers_per_faction.max(1))
+                    .cloned()
+                    .collect::<Vec<_>>();
+                Some((*digest, random_validators))
             } else {
                 None
             }
         })
-        .collect::<HashMap<_, _>>();
-    if digest_to_validator.is_empty() {
+        .collect::<BTreeMap<_, _>>();
+    if digest_to_validators.is_empty() {
         panic!(
             "Given split brain condition, there should be at \
                 least one validator that disagrees with local signature"
@@ -1541,12 +3911,15 @@ async fn diagnose_split_brain(
             .expect("Failed to make authority clients from committee {committee}");
 
     // Query all disagreeing validators
-    let response_futures = digest_to_validator
-        .values()
-        .cloned()
-        .map(|validator| {
+    let queried = digest_to_validators
+        .iter()
+        .flat_map(|(digest, validators)| validators.iter().map(|validator| (*digest, *validator)))
+        .collect::<Vec<_>>();
+    let response_futures = queried
+        .iter()
+        .map(|(_, validator)| {
             let client = network_clients
-                .get(&validator)
+                .get(validator)
                 .expect("Failed to get network client");
             let request = CheckpointRequestV2 {
                 sequence_number: Some(local_summary.sequence_number),
@@ -1557,11 +3930,10 @@ async fn diagnose_split_brain(
         })
         .collect::<Vec<_>>();
 
-    let digest_name_pair = digest_to_validator.iter();
-    let response_data = futures::future::join_all(response_futures)
+    let responses = futures::future::join_all(response_futures)
         .await
         .into_iter()
-        .zip(digest_name_pair)
+        .zip(queried.iter())
         .filter_map(|(response, (digest, name))| match response {
             Ok(response) => match response {
                 CheckpointResponseV2 {
@@ -1605,6 +3977,104 @@ async fn diagnose_split_brain(
         })
         .collect::<Vec<_>>();
 
+    // Keep one response per faction, preferring a validator whose summary still has the digest
+    // it signed.
+    let mut response_data: Vec<(
+        AuthorityName,
+        CheckpointDigest,
+        CheckpointSummary,
+        CheckpointContents,
+    )> = vec![];
+    for (name, digest, summary, contents) in responses {
+        if summary.digest() != digest {
+            warn!(
+                "Validator {:?} returned checkpoint digest {:?} for fork diagnostics, but signed {:?}",
+                name,
+                summary.digest(),
+                digest
+            );
+        }
+        match response_data
+            .iter_mut()
+            .find(|(_, faction, _, _)| *faction == digest)
+        {
+            Some(selected) => {
+                if selected.2.digest() != digest && summary.digest() == digest {
+                    *selected = (name, digest, summary, contents);
+                }
+            }
+            None => response_data.push((name, digest, summary, contents)),
+        }
+    }
+    for digest in digest_to_validators.keys() {
+        if !response_data
+            .iter()
+            .any(|(_, faction, _, _)| faction == digest)
+        {
+            error!(
+                "No validator of the faction with digest {:?} responded for fork diagnostics",
+                digest
+            );
+        }
+    }
+
+    // Digests of the checkpoints preceding the forked one, locally and on each disagreeing
+    // validator, to tell whether the fork is new or inherited from an earlier checkpoint.
+    let lookback_range =
+        local_summary.sequence_number.saturating_sub(lookback)..local_summary.sequence_number;
+    let local_prior_digests = lookback_range
+        .clone()
+        .map(|seq| {
+            tables
+                .get_checkpoint_by_sequence_number(seq)
+                .ok()
+                .flatten()
+                .map(|checkpoint| *checkpoint.digest())
+                .or_else(|| {
+                    tables
+                        .locally_computed_checkpoints
+                        .get(&seq)
+                        .ok()
+                        .flatten()
+                        .map(|summary| summary.digest())
+                })
+        })
+        .collect::<Vec<_>>();
+    let mut other_prior_digests = HashMap::new();
+    for (name, _, _, _) in &response_data {
+        let client = network_clients
+            .get(name)
+            .expect("Failed to get network client");
+        let mut digests = vec![];
+        for seq in lookback_range.clone() {
+            let request = CheckpointRequestV2 {
+                sequence_number: Some(seq),
+                request_content: false,
+                certified: true,
+            };
+            let digest = match client.handle_checkpoint_v2(request).await {
+                Ok(CheckpointResponseV2 {
+                    checkpoint: Some(CheckpointSummaryResponse::Certified(summary)),
+                    ..
+                }) => Some(*summary.digest()),
+                Ok(CheckpointResponseV2 {
+                    checkpoint: Some(CheckpointSummaryResponse::Pending(summary)),
+                    ..
+                }) => Some(summary.digest()),
+                Ok(_) => None,
+                Err(e) => {
+                    error!(
+                        "Failed to get checkpoint {seq} from validator for fork diagnostics: {:?}",
+                        e
+                    );
+                    None
+                }
+            };
+            digests.push(digest);
+        }
+        other_prior_digests.insert(*name, digests);
+    }
+
     let local_checkpoint_contents = tables
         .get_checkpoint_contents(&local_summary.content_digest)
         .unwrap_or_else(|_| {
@@ -1620,32 +4090,28 @@ async fn diagnose_split_brain(
                 local_summary.digest()
             )
         });
-    let local_contents_text = format!("{local_checkpoint_contents:?}");
 
-    let local_summary_text = format!("{local_summary:?}");
     let local_validator = state.name.concise();
     let diff_patches = response_data
         .iter()
         .map(|(name, other_digest, other_summary, contents)| {
-            let other_contents_text = format!("{contents:?}");
-            let other_summary_text = format!("{other_summary:?}");
-            let (local_transactions, local_effects): (Vec<_>, Vec<_>) = local_checkpoint_contents
-                .enumerate_transactions(&local_summary)
-                .map(|(_, exec_digest)| (exec_digest.transaction, exec_digest.effects))
-                .unzip();
-            let (other_transactions, other_effects): (Vec<_>, Vec<_>) = contents
-                .enumerate_transactions(other_summary)
-                .map(|(_, exec_digest)| (exec_digest.transaction, exec_digest.effects))
-                .unzip();
-            let summary_patch = create_patch(&local_summary_text, &other_summary_text);
-            let contents_patch = create_patch(&local_contents_text, &other_contents_text);
-            let local_transactions_text = format!("{local_transactions:#?}");
-            let other_transactions_text = format!("{other_transactions:#?}");
-            let transactions_patch =
-                create_patch(&local_transactions_text, &other_transactions_text);
-            let local_effects_text = format!("{local_effects:#?}");
-            let other_effects_text = format!("{other_effects:#?}");
-            let effects_patch = create_patch(&local_effects_text, &other_effects_text);
+            let report = build_fork_report(
+                &local_summary,
+                &local_checkpoint_contents,
+                other_summary,
+                contents,
+            )
+            .text();
+            let prior_digests_text = lookback_range
+                .clone()
+                .zip(local_prior_digests.iter())
+                .zip(other_prior_digests[name].iter())
+                .map(|((seq, local), other)| {
+                    let marker = if local == other { "" } else { " MISMATCH" };
+                    format!("{seq}: local {local:?}, other {other:?}{marker}")
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
             let seq_number = local_summary.sequence_number;
             let local_digest = local_summary.digest();
             let other_validator = name.concise();
@@ -1653,26 +4119,39 @@ async fn diagnose_split_brain(
                 "Checkpoint: {seq_number:?}\n\
                 Local validator (original): {local_validator:?}, digest: {local_digest:?}\n\
                 Other validator (modified): {other_validator:?}, digest: {other_digest:?}\n\n\
-                Summary Diff: \n{summary_patch}\n\n\
-                Contents Diff: \n{contents_patch}\n\n\
-                Transactions Diff: \n{transactions_patch}\n\n\
-                Effects Diff: \n{effects_patch}",
+                Prior Checkpoints: \n{prior_digests_text}\n\n\
+                {report}",
             )
         })
         .collect::<Vec<_>>()
         .join("\n\n\n");
 
+    let json_header = serde_json::to_string(&ForkDumpHeader {
+        checkpoint_seq: local_summary.sequence_number,
+        epoch: local_summary.epoch,
+        local_digest: local_summary.digest(),
+        others: queried
+            .iter()
+            .map(|(digest, validator)| (*validator, *digest))
+            .collect(),
+        version: env!("CARGO_PKG_VERSION"),
+    })
+    .expect("Failed to serialize fork dump header");
     let header = format!(
         "Checkpoint Fork Dump - Authority {local_validator:?}: \n\
         Datetime: {time}",
     );
-    let fork_logs_text = format!("{header}\n\n{diff_patches}\n\n");
-    let path = tempfile::tempdir()
-        .expect("Failed to create tempdir")
-        .into_path()
-        .join(Path::new("checkpoint_fork_dump.txt"));
-    let mut file = File::create(path).unwrap();
-    write!(file, "{}", fork_logs_text).unwrap();
+    let fork_logs_text = format!("{json_header}\n{header}\n\n{diff_patches}\n\n");
+    match write_fork_dump(
+        &std::env::temp_dir().join(FORK_DUMPS_DIR),
+        local_summary.sequence_number,
+        &time,
+        &fork_logs_text,
+        max_retained_fork_dumps,
+    ) {
+        Ok(path) => info!("Checkpoint fork dump written to {}", path.display()),
+        Err(e) => error!("Failed to write checkpoint fork dump: {:?}", e),
+    }
     debug!("{}", fork_logs_text);
 
     fail_point!("split_brain_reached");
@@ -1700,6 +4179,14 @@ pub struct CheckpointService {
     notify_aggregator: Arc<Notify>,
     last_signature_index: Mutex<u64>,
     metrics: Arc<CheckpointMetrics>,
+    highest_certified: watch::Receiver<CheckpointSequenceNumber>,
+    certified_events: broadcast::Sender<CertifiedCheckpointEvent>,
+    pending_signature_stake: watch::Receiver<Option<(CheckpointSequenceNumber, StakeUnit)>>,
+    split_brain_report: Arc<Mutex<Option<SplitBrainReport>>>,
+    certification_ceiling: Arc<Mutex<Option<CheckpointSequenceNumber>>>,
+    reaggregate_request: Arc<Mutex<Option<CheckpointSequenceNumber>>>,
+    epoch_participation: Arc<Mutex<EpochParticipation>>,
+    epoch_store: Arc<AuthorityPerEpochStore>,
 }
 
 impl CheckpointService {
@@ -1714,15 +4201,23 @@ impl CheckpointService {
         metrics: Arc<CheckpointMetrics>,
         max_transactions_per_checkpoint: usize,
         max_checkpoint_size_bytes: usize,
-    ) -> (Arc<Self>, watch::Sender<()> /* The exit sender */) {
+        config: CheckpointServiceConfig,
+    ) -> SuiResult<(Arc<Self>, watch::Sender<()> /* The exit sender */)> {
         info!(
             "Starting checkpoint service with {max_transactions_per_checkpoint} max_transactions_per_checkpoint and {max_checkpoint_size_bytes} max_checkpoint_size_bytes"
         );
+        check_size_limits(max_transactions_per_checkpoint, max_checkpoint_size_bytes)?;
         let notify_builder = Arc::new(Notify::new());
         let notify_aggregator = Arc::new(Notify::new());
 
         let (exit_snd, exit_rcv) = watch::channel(());
 
+        let highest_certified_seq = checkpoint_store
+            .get_latest_certified_checkpoint()
+            .map(|checkpoint| *checkpoint.sequence_number())
+            .unwrap_or_default();
+        let (highest_certified_snd, highest_certified_rcv) = watch::channel(highest_certified_seq);
+
         let builder = CheckpointBuilder::new(
             state.clone(),
             checkpoint_store.clone(),
@@ -1736,10 +4231,19 @@ impl CheckpointService {
             metrics.clone(),
             max_transactions_per_checkpoint,
             max_checkpoint_size_bytes,
+            highest_certified_rcv.clone(),
+            &config,
         );
 
         spawn_monitored_task!(builder.run());
 
+        let (certified_events, _) = broadcast::channel(CERTIFIED_EVENTS_CHANNEL_CAPACITY);
+        let (pending_signature_stake_snd, pending_signature_stake_rcv) = watch::channel(None);
+        let split_brain_report = Arc::new(Mutex::new(None));
+        let certification_ceiling = Arc::new(Mutex::new(config.certification_ceiling));
+        let reaggregate_request = Arc::new(Mutex::new(None));
+        let epoch_participation = Arc::new(Mutex::new(EpochParticipation::default()));
+
         let aggregator = CheckpointAggregator::new(
             checkpoint_store.clone(),
             epoch_store.clone(),
@@ -1748,6 +4252,16 @@ impl CheckpointService {
             certified_checkpoint_output,
             state.clone(),
             metrics.clone(),
+            CheckpointAggregatorShared {
+                highest_certified: highest_certified_snd,
+                certified_events: certified_events.clone(),
+                pending_signature_stake: pending_signature_stake_snd,
+                split_brain_report: split_brain_report.clone(),
+                certification_ceiling: certification_ceiling.clone(),
+                reaggregate_request: reaggregate_request.clone(),
+                epoch_participation: epoch_participation.clone(),
+            },
+            &config,
         );
 
         spawn_monitored_task!(aggregator.run());
@@ -1763,47 +4277,155 @@ impl CheckpointService {
             notify_aggregator,
             last_signature_index,
             metrics,
+            highest_certified: highest_certified_rcv,
+            certified_events,
+            pending_signature_stake: pending_signature_stake_rcv,
+            split_brain_report,
+            certification_ceiling,
+            reaggregate_request,
+            epoch_participation,
+            epoch_store,
         });
-        (service, exit_snd)
+        Ok((service, exit_snd))
     }
 
-    #[cfg(test)]
-    fn write_and_notify_checkpoint_for_testing(
-        &self,
-        epoch_store: &AuthorityPerEpochStore,
-        checkpoint: PendingCheckpoint,
-    ) -> SuiResult {
-        let mut batch = epoch_store.db_batch_for_test();
-        epoch_store.write_pending_checkpoint(&mut batch, &checkpoint)?;
-        batch.write()?;
-        self.notify_checkpoint(&checkpoint)?;
+    /// Returns the stake of the signatures accumulated so far for the checkpoint with the given
+    /// sequence number, if it is the checkpoint currently being aggregated. The value is updated
+    /// by the aggregator once per aggregation round.
+    pub fn pending_signature_stake(&self, seq: CheckpointSequenceNumber) -> Option<StakeUnit> {
+        let pending = *self.pending_signature_stake.borrow();
+        pending
+            .filter(|(pending_seq, _)| *pending_seq == seq)
+            .map(|(_, stake)| stake)
+    }
+
+    /// Returns how far the checkpoint builder is behind consensus.
+    pub fn builder_status(&self) -> SuiResult<BuilderStatus> {
+        let last_built_height = self.epoch_store.last_built_checkpoint_commit_height()?;
+        let last_built_seq = self
+            .epoch_store
+            .last_built_checkpoint_summary()?
+            .map(|(seq, _)| seq);
+        let pending_commits = self
+            .epoch_store
+            .get_pending_checkpoints(last_built_height)?
+            .len();
+        Ok(BuilderStatus {
+            last_built_height,
+            last_built_seq,
+            pending_commits,
+        })
+    }
+
+    /// Returns the consensus commit height of the last pending checkpoint processed by the
+    /// checkpoint builder, as last reported by the builder. This is the value of the
+    /// `builder_last_commit_height` gauge; it is 0 until the builder processed a pending checkpoint.
+    pub fn builder_last_commit_height(&self) -> CheckpointCommitHeight {
+        self.metrics.builder_last_commit_height.get() as CheckpointCommitHeight
+    }
+
+    /// Returns the breakdown of the latest split brain detected by the checkpoint aggregator, if
+    /// any was detected since the service was spawned.
+    pub fn latest_split_brain_report(&self) -> Option<SplitBrainReport> {
+        self.split_brain_report.lock().clone()
+    }
+
+    /// Returns, for each validator of the committee, the fraction of the checkpoints certified
+    /// by the aggregator in this epoch for which its signature was aggregated. Signatures
+    /// received after a checkpoint reached quorum are not aggregated, and the counts start
+    /// over when the service is spawned again, e.g. after a restart.
+    pub fn epoch_participation(&self) -> HashMap<AuthorityName, f64> {
+        self.epoch_participation
+            .lock()
+            .fractions(self.epoch_store.committee())
+    }
+
+    /// Changes the sequence number above which the aggregator stops certifying checkpoints, or
+    /// lifts the freeze with None. The aggregator is notified so that certification resumes
+    /// right away when the ceiling is raised or lifted.
+    pub fn set_certification_ceiling(&self, ceiling: Option<CheckpointSequenceNumber>) {
+        info!("Setting checkpoint certification ceiling to {ceiling:?}");
+        *self.certification_ceiling.lock() = ceiling;
+        self.notify_aggregator.notify_one();
+    }
+
+    /// Makes the aggregator rebuild its signature aggregator for `seq` from the pending
+    /// signatures in the epoch store, to recover from a stuck or desynced aggregator without a
+    /// restart. Fails if `seq` is already certified. The current aggregator is only dropped if it
+    /// points at a checkpoint other than `seq`. Checkpoints are certified in order, so for a later
+    /// `seq` the request stays pending until `seq` is the next checkpoint to certify.
+    pub fn force_reaggregate(&self, seq: CheckpointSequenceNumber) -> SuiResult {
+        let next_to_certify = self
+            .last_certified_sequence()?
+            .map(|last| last + 1)
+            .unwrap_or_default();
+        if seq < next_to_certify {
+            return Err(SuiError::GenericAuthorityError {
+                error: format!(
+                    "Checkpoint {seq} is already certified, next checkpoint to certify is {next_to_certify}"
+                ),
+            });
+        }
+        *self.reaggregate_request.lock() = Some(seq);
+        self.notify_aggregator.notify_one();
         Ok(())
     }
-}
 
-impl CheckpointServiceNotify for CheckpointService {
-    fn notify_checkpoint_signature(
+    /// Same as `notify_checkpoint_signature` for many signatures at once, e.g. when replaying
+    /// buffered signatures after a restart. Signatures of already certified checkpoints are
+    /// skipped, the others are given contiguous indices and written in a single batch, and the
+    /// aggregator is notified once.
+    pub fn notify_checkpoint_signatures_batch(
         &self,
         epoch_store: &AuthorityPerEpochStore,
-        info: &CheckpointSignatureMessage,
+        msgs: &[CheckpointSignatureMessage],
     ) -> SuiResult {
-        let sequence = info.summary.sequence_number;
-        let signer = info.summary.auth_sig().authority.concise();
-        if let Some(last_certified) = self
+        let last_certified = self.last_certified_sequence()?;
+        let accepted: Vec<_> = msgs
+            .iter()
+            .filter(|info| self.accept_checkpoint_signature(last_certified, info))
+            .collect();
+        if accepted.is_empty() {
+            return Ok(());
+        }
+        let mut index = self.last_signature_index.lock();
+        let first_index = *index + 1;
+        epoch_store.insert_checkpoint_signatures(
+            accepted
+                .iter()
+                .zip(first_index..)
+                .map(|(info, index)| (info.summary.sequence_number, index, *info)),
+        )?;
+        *index += accepted.len() as u64;
+        self.notify_aggregator.notify_one();
+        Ok(())
+    }
+
+    fn last_certified_sequence(&self) -> SuiResult<Option<CheckpointSequenceNumber>> {
+        Ok(self
             .tables
             .certified_checkpoints
             .keys()
             .skip_to_last()
             .next()
-            .transpose()?
-        {
-            if sequence <= last_certified {
-                debug!(
-                    checkpoint_seq = sequence,
-                    "Ignore checkpoint signature from {} - already certified", signer,
-                );
-                return Ok(());
-            }
+            .transpose()?)
+    }
+
+    /// Returns whether a checkpoint signature should be recorded, i.e. whether its checkpoint is
+    /// not certified yet, and updates the signature metrics if so.
+    fn accept_checkpoint_signature(
+        &self,
+        last_certified: Option<CheckpointSequenceNumber>,
+        info: &CheckpointSignatureMessage,
+    ) -> bool {
+        let sequence = info.summary.sequence_number;
+        let signer = info.summary.auth_sig().authority.concise();
+        if last_certified.is_some_and(|last_certified| sequence <= last_certified) {
+            debug!(
+                checkpoint_seq = sequence,
+                "Ignore checkpoint signature from {} - already certified", signer,
+            );
+            return false;
         }
         debug!(
             checkpoint_seq = sequence,
@@ -1815,11 +4437,55 @@ impl CheckpointServiceNotify for CheckpointService {
             .last_received_checkpoint_signatures
             .with_label_values(&[&signer.to_string()])
             .set(sequence as i64);
+        true
+    }
+
+    /// Returns a receiver that is updated with the sequence number of each checkpoint
+    /// certified by the local aggregator, and with the highest checkpoint certified through
+    /// state sync after each aggregation round. The initial value is the highest certified
+    /// checkpoint at the time the service was spawned (or 0 if there was none).
+    pub fn subscribe_to_certified(&self) -> watch::Receiver<CheckpointSequenceNumber> {
+        self.highest_certified.clone()
+    }
+
+    /// Returns a receiver of an event for each checkpoint certified by the local aggregator
+    /// from now on. Each receiver buffers up to `CERTIFIED_EVENTS_CHANNEL_CAPACITY` events: a
+    /// receiver that falls further behind loses the oldest events and gets
+    /// `RecvError::Lagged` with the number of events it missed. Checkpoints obtained through
+    /// state sync are not reported.
+    pub fn subscribe_certified_events(&self) -> broadcast::Receiver<CertifiedCheckpointEvent> {
+        self.certified_events.subscribe()
+    }
+
+    #[cfg(test)]
+    fn write_and_notify_checkpoint_for_testing(
+        &self,
+        epoch_store: &AuthorityPerEpochStore,
+        checkpoint: PendingCheckpoint,
+    ) -> SuiResult {
+        let mut batch = epoch_store.db_batch_for_test();
+        epoch_store.write_pending_checkpoint(&mut batch, &checkpoint)?;
+        batch.write()?;
+        self.notify_checkpoint(&checkpoint)?;
+        Ok(())
+    }
+}
+
+impl CheckpointServiceNotify for CheckpointService {
+    fn notify_checkpoint_signature(
+        &self,
+        epoch_store: &AuthorityPerEpochStore,
+        info: &CheckpointSignatureMessage,
+    ) -> SuiResult {
+        let last_certified = self.last_certified_sequence()?;
+        if !self.accept_checkpoint_signature(last_certified, info) {
+            return Ok(());
+        }
         // While it can be tempting to make last_signature_index into AtomicU64, this won't work
         // We need to make sure we write to `pending_signatures` and trigger `notify_aggregator` without race conditions
         let mut index = self.last_signature_index.lock();
         *index += 1;
-        epoch_store.insert_checkpoint_signature(sequence, *index, info)?;
+        epoch_store.insert_checkpoint_signature(info.summary.sequence_number, *index, info)?;
         self.notify_aggregator.notify_one();
         Ok(())
     }
@@ -1861,12 +4527,16 @@ mod tests {
     use super::*;
     use crate::authority::test_authority_builder::TestAuthorityBuilder;
     use async_trait::async_trait;
+    use fastcrypto::traits::KeyPair;
     use shared_crypto::intent::{Intent, IntentScope};
     use std::collections::{BTreeMap, HashMap};
     use std::ops::Deref;
     use sui_macros::sim_test;
-    use sui_types::base_types::{ObjectID, SequenceNumber, TransactionEffectsDigest};
-    use sui_types::crypto::{AuthoritySignInfo, Signature};
+    use sui_types::base_types::{
+        ExecutionDigests, ObjectID, SequenceNumber, TransactionEffectsDigest,
+    };
+    use sui_types::committee::Committee;
+    use sui_types::crypto::{AuthorityKeyPair, AuthoritySignInfo, Signature};
     use sui_types::effects::TransactionEffects;
     use sui_types::messages_checkpoint::SignedCheckpointSummary;
     use sui_types::move_package::MovePackage;
@@ -1979,104 +4649,845 @@ mod tests {
 
         let accumulator = StateAccumulator::new(state.database.clone());
 
-        let epoch_store = state.epoch_store_for_testing();
-        let (checkpoint_service, _exit) = CheckpointService::spawn(
+        let epoch_store = state.epoch_store_for_testing();
+        let (checkpoint_service, _exit) = CheckpointService::spawn(
+            state.clone(),
+            checkpoint_store,
+            epoch_store.clone(),
+            store,
+            Arc::new(accumulator),
+            Box::new(output),
+            Box::new(certified_output),
+            CheckpointMetrics::new_for_tests(),
+            3,
+            100_000,
+            CheckpointServiceConfig::default(),
+        )
+        .unwrap();
+
+        checkpoint_service
+            .write_and_notify_checkpoint_for_testing(&epoch_store, p(0, vec![4]))
+            .unwrap();
+        // Verify that sending same digests at same height is noop
+        checkpoint_service
+            .write_and_notify_checkpoint_for_testing(&epoch_store, p(0, vec![4]))
+            .unwrap();
+        checkpoint_service
+            .write_and_notify_checkpoint_for_testing(&epoch_store, p(1, vec![1, 3]))
+            .unwrap();
+        checkpoint_service
+            .write_and_notify_checkpoint_for_testing(&epoch_store, p(2, vec![10, 11, 12, 13]))
+            .unwrap();
+        checkpoint_service
+            .write_and_notify_checkpoint_for_testing(&epoch_store, p(3, vec![15, 16, 17]))
+            .unwrap();
+
+        let (c1c, c1s) = result.recv().await.unwrap();
+        let (c2c, c2s) = result.recv().await.unwrap();
+
+        let c1t = c1c.iter().map(|d| d.transaction).collect::<Vec<_>>();
+        let c2t = c2c.iter().map(|d| d.transaction).collect::<Vec<_>>();
+        assert_eq!(c1t, vec![d(4)]);
+        assert_eq!(c1s.previous_digest, None);
+        assert_eq!(c1s.sequence_number, 0);
+        assert_eq!(
+            c1s.epoch_rolling_gas_cost_summary,
+            GasCostSummary::new(41, 42, 41, 1)
+        );
+
+        assert_eq!(c2t, vec![d(3), d(2), d(1)]);
+        assert_eq!(c2s.previous_digest, Some(c1s.digest()));
+        assert_eq!(c2s.sequence_number, 1);
+        assert_eq!(
+            c2s.epoch_rolling_gas_cost_summary,
+            GasCostSummary::new(104, 108, 104, 4)
+        );
+
+        // Pending at index 2 had 4 transactions, and we configured 3 transactions max.
+        // Verify that we split into 2 checkpoints.
+        let (c3c, c3s) = result.recv().await.unwrap();
+        let c3t = c3c.iter().map(|d| d.transaction).collect::<Vec<_>>();
+        let (c4c, c4s) = result.recv().await.unwrap();
+        let c4t = c4c.iter().map(|d| d.transaction).collect::<Vec<_>>();
+        assert_eq!(c3s.sequence_number, 2);
+        assert_eq!(c3s.previous_digest, Some(c2s.digest()));
+        assert_eq!(c4s.sequence_number, 3);
+        assert_eq!(c4s.previous_digest, Some(c3s.digest()));
+        assert_eq!(c3t, vec![d(10), d(11), d(12)]);
+        assert_eq!(c4t, vec![d(13)]);
+
+        // Pending at index 3 had 3 transactions of 40K size, and we configured 100K max.
+        // Verify that we split into 2 checkpoints.
+        let (c5c, c5s) = result.recv().await.unwrap();
+        let c5t = c5c.iter().map(|d| d.transaction).collect::<Vec<_>>();
+        let (c6c, c6s) = result.recv().await.unwrap();
+        let c6t = c6c.iter().map(|d| d.transaction).collect::<Vec<_>>();
+        assert_eq!(c5s.sequence_number, 4);
+        assert_eq!(c5s.previous_digest, Some(c4s.digest()));
+        assert_eq!(c6s.sequence_number, 5);
+        assert_eq!(c6s.previous_digest, Some(c5s.digest()));
+        assert_eq!(c5t, vec![d(15), d(16)]);
+        assert_eq!(c6t, vec![d(17)]);
+
+        let c1ss = SignedCheckpointSummary::new(c1s.epoch, c1s, state.secret.deref(), state.name);
+        let c2ss = SignedCheckpointSummary::new(c2s.epoch, c2s, state.secret.deref(), state.name);
+
+        checkpoint_service
+            .notify_checkpoint_signature(
+                &epoch_store,
+                &CheckpointSignatureMessage { summary: c2ss },
+            )
+            .unwrap();
+        checkpoint_service
+            .notify_checkpoint_signature(
+                &epoch_store,
+                &CheckpointSignatureMessage { summary: c1ss },
+            )
+            .unwrap();
+
+        let c1sc = certified_result.recv().await.unwrap();
+        let c2sc = certified_result.recv().await.unwrap();
+        assert_eq!(c1sc.sequence_number, 0);
+        assert_eq!(c2sc.sequence_number, 1);
+    }
+
+    #[sim_test]
+    pub async fn checkpoint_builder_retries_missing_transaction_test() {
+        telemetry_subscribers::init_for_testing();
+        let state = TestAuthorityBuilder::new().build().await;
+
+        // Effects are available, but the transaction itself is not yet in the store.
+        let mut store = HashMap::<TransactionDigest, TransactionEffects>::new();
+        commit_cert_for_test(
+            &mut store,
+            state.clone(),
+            d(1),
+            vec![],
+            GasCostSummary::new(11, 12, 11, 1),
+        );
+        let signature = Signature::Ed25519SuiSignature(Default::default()).into();
+        state
+            .epoch_store_for_testing()
+            .test_insert_user_signature(d(1), vec![signature]);
+
+        let mut test = spawn_service_for_test(&state, store, CheckpointServiceConfig::default());
+
+        test.service
+            .write_and_notify_checkpoint_for_testing(&test.epoch_store, p(0, vec![1]))
+            .unwrap();
+
+        // The builder reports an error and keeps running instead of panicking.
+        test.assert_build_fails().await;
+
+        // Once the transaction shows up, the next retry produces the checkpoint.
+        let dummy_tx = VerifiedTransaction::new_genesis_transaction(vec![]);
+        state
+            .database
+            .perpetual_tables
+            .transactions
+            .insert(&d(1), dummy_tx.serializable_ref())
+            .unwrap();
+        let (contents, summary) = test.output.recv().await.unwrap();
+        assert_eq!(summary.sequence_number, 0);
+        assert_eq!(contents.size(), 1);
+        assert_eq!(contents.iter().next().unwrap().transaction, d(1));
+    }
+
+    #[sim_test]
+    pub async fn checkpoint_builder_rejects_timestamp_regression_test() {
+        telemetry_subscribers::init_for_testing();
+        let state = TestAuthorityBuilder::new().build().await;
+
+        let dummy_tx = VerifiedTransaction::new_genesis_transaction(vec![]);
+        let mut store = HashMap::<TransactionDigest, TransactionEffects>::new();
+        for i in 1..=2 {
+            state
+                .database
+                .perpetual_tables
+                .transactions
+                .insert(&d(i), dummy_tx.serializable_ref())
+                .unwrap();
+            commit_cert_for_test(
+                &mut store,
+                state.clone(),
+                d(i),
+                vec![],
+                GasCostSummary::new(11, 12, 11, 1),
+            );
+            let signature = Signature::Ed25519SuiSignature(Default::default()).into();
+            state
+                .epoch_store_for_testing()
+                .test_insert_user_signature(d(i), vec![signature]);
+        }
+
+        let mut test = spawn_service_for_test(
+            &state,
+            store,
+            CheckpointServiceConfig {
+                reject_timestamp_regression: true,
+                ..Default::default()
+            },
+        );
+
+        let mut first = p(0, vec![1]);
+        first.details.timestamp_ms = 1000;
+        test.service
+            .write_and_notify_checkpoint_for_testing(&test.epoch_store, first)
+            .unwrap();
+        let (_, summary) = test.output.recv().await.unwrap();
+        assert_eq!(summary.timestamp_ms, 1000);
+
+        // The next checkpoint goes back in time, so it is not built.
+        let mut second = p(1, vec![2]);
+        second.details.timestamp_ms = 500;
+        test.service
+            .write_and_notify_checkpoint_for_testing(&test.epoch_store, second)
+            .unwrap();
+        test.assert_build_fails().await;
+    }
+
+    #[sim_test]
+    pub async fn checkpoint_builder_rejects_misaligned_transaction_test() {
+        telemetry_subscribers::init_for_testing();
+        let state = TestAuthorityBuilder::new().build().await;
+
+        // The transaction stored under d(1) is a different transaction, so it does not match
+        // the effects of d(1).
+        let dummy_tx = VerifiedTransaction::new_genesis_transaction(vec![]);
+        let mut store = HashMap::<TransactionDigest, TransactionEffects>::new();
+        state
+            .database
+            .perpetual_tables
+            .transactions
+            .insert(&d(1), dummy_tx.serializable_ref())
+            .unwrap();
+        commit_cert_for_test(
+            &mut store,
             state.clone(),
-            checkpoint_store,
-            epoch_store.clone(),
+            d(1),
+            vec![],
+            GasCostSummary::new(11, 12, 11, 1),
+        );
+        let signature = Signature::Ed25519SuiSignature(Default::default()).into();
+        state
+            .epoch_store_for_testing()
+            .test_insert_user_signature(d(1), vec![signature]);
+
+        let mut test = spawn_service_for_test(
+            &state,
             store,
-            Arc::new(accumulator),
-            Box::new(output),
-            Box::new(certified_output),
-            CheckpointMetrics::new_for_tests(),
-            3,
-            100_000,
+            CheckpointServiceConfig {
+                validate_transaction_digests: true,
+                ..Default::default()
+            },
         );
 
-        checkpoint_service
-            .write_and_notify_checkpoint_for_testing(&epoch_store, p(0, vec![4]))
-            .unwrap();
-        // Verify that sending same digests at same height is noop
-        checkpoint_service
-            .write_and_notify_checkpoint_for_testing(&epoch_store, p(0, vec![4]))
-            .unwrap();
-        checkpoint_service
-            .write_and_notify_checkpoint_for_testing(&epoch_store, p(1, vec![1, 3]))
+        test.service
+            .write_and_notify_checkpoint_for_testing(&test.epoch_store, p(0, vec![1]))
             .unwrap();
-        checkpoint_service
-            .write_and_notify_checkpoint_for_testing(&epoch_store, p(2, vec![10, 11, 12, 13]))
+
+        // The builder reports an error and retries instead of building the checkpoint.
+        test.assert_build_fails().await;
+    }
+
+    #[tokio::test]
+    async fn test_end_of_epoch_checkpoint_must_follow_recorded_epochs() {
+        let (committee, key_pairs) = Committee::new_simple_test_committee();
+        let end_of_epoch = |epoch: EpochId, seq: CheckpointSequenceNumber| {
+            let epoch_committee =
+                Committee::new(epoch, committee.voting_rights.iter().cloned().collect());
+            let contents =
+                CheckpointContents::new_with_digests_only_for_tests([ExecutionDigests::random()]);
+            certify_for_test(
+                &epoch_committee,
+                &key_pairs,
+                CheckpointSummary::new(
+                    epoch,
+                    seq,
+                    seq + 1,
+                    &contents,
+                    None,
+                    GasCostSummary::default(),
+                    Some(EndOfEpochData {
+                        next_epoch_committee: committee.voting_rights.clone(),
+                        next_epoch_protocol_version: ProtocolVersion::MIN,
+                        epoch_commitments: vec![],
+                    }),
+                    0,
+                ),
+            )
+        };
+
+        let ckpt_dir = tempfile::tempdir().unwrap();
+        let store = CheckpointStore::new(ckpt_dir.path());
+        let epoch_0 = end_of_epoch(0, 0);
+        store.insert_certified_checkpoint(&epoch_0).unwrap();
+        store.check_certified_checkpoint(&epoch_0).unwrap();
+        store
+            .check_certified_checkpoint(&end_of_epoch(1, 1))
             .unwrap();
-        checkpoint_service
-            .write_and_notify_checkpoint_for_testing(&epoch_store, p(3, vec![15, 16, 17]))
+        store
+            .insert_certified_checkpoint(&end_of_epoch(1, 1))
             .unwrap();
 
-        let (c1c, c1s) = result.recv().await.unwrap();
-        let (c2c, c2s) = result.recv().await.unwrap();
+        // Epoch 2 is skipped.
+        let epoch_3 = end_of_epoch(3, 2);
+        assert!(store.check_certified_checkpoint(&epoch_3).is_err());
+        assert!(store.get_epoch_last_checkpoint(3).unwrap().is_none());
+        assert!(store
+            .get_checkpoint_by_sequence_number(2)
+            .unwrap()
+            .is_none());
 
-        let c1t = c1c.iter().map(|d| d.transaction).collect::<Vec<_>>();
-        let c2t = c2c.iter().map(|d| d.transaction).collect::<Vec<_>>();
-        assert_eq!(c1t, vec![d(4)]);
-        assert_eq!(c1s.previous_digest, None);
-        assert_eq!(c1s.sequence_number, 0);
+        // A different last checkpoint for an already recorded epoch is rejected as well.
+        assert!(store
+            .check_certified_checkpoint(&end_of_epoch(1, 2))
+            .is_err());
         assert_eq!(
-            c1s.epoch_rolling_gas_cost_summary,
-            GasCostSummary::new(41, 42, 41, 1)
+            store
+                .get_epoch_last_checkpoint(1)
+                .unwrap()
+                .unwrap()
+                .sequence_number,
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn test_epoch_zero_committee_is_taken_from_genesis() {
+        let (committee, key_pairs) = Committee::new_simple_test_committee();
+        let contents =
+            CheckpointContents::new_with_digests_only_for_tests([ExecutionDigests::random()]);
+        // Leave the last validator out of the signers.
+        let checkpoint = certify_for_test(
+            &committee,
+            &key_pairs[..3],
+            CheckpointSummary::new(0, 0, 1, &contents, None, GasCostSummary::default(), None, 0),
         );
 
-        assert_eq!(c2t, vec![d(3), d(2), d(1)]);
-        assert_eq!(c2s.previous_digest, Some(c1s.digest()));
-        assert_eq!(c2s.sequence_number, 1);
+        let ckpt_dir = tempfile::tempdir().unwrap();
+        let store = CheckpointStore::new(ckpt_dir.path());
+        store.insert_certified_checkpoint(&checkpoint).unwrap();
+        assert!(store.committee_for_epoch(0).unwrap().is_none());
+        assert!(store.certification_details(0).is_err());
+
+        store.insert_genesis_committee(&committee).unwrap();
         assert_eq!(
-            c2s.epoch_rolling_gas_cost_summary,
-            GasCostSummary::new(104, 108, 104, 4)
+            store.committee_for_epoch(0).unwrap(),
+            Some(committee.clone())
         );
+        let details = store.certification_details(0).unwrap().unwrap();
+        assert_eq!(details.signers.len(), 3);
+        assert_eq!(details.total_stake, committee.total_votes());
+        let absent: AuthorityName = key_pairs[3].public().into();
+        assert_eq!(store.absent_validators_in_epoch(0).unwrap(), vec![absent]);
+    }
 
-        // Pending at index 2 had 4 transactions, and we configured 3 transactions max.
-        // Verify that we split into 2 checkpoints.
-        let (c3c, c3s) = result.recv().await.unwrap();
-        let c3t = c3c.iter().map(|d| d.transaction).collect::<Vec<_>>();
-        let (c4c, c4s) = result.recv().await.unwrap();
-        let c4t = c4c.iter().map(|d| d.transaction).collect::<Vec<_>>();
-        assert_eq!(c3s.sequence_number, 2);
-        assert_eq!(c3s.previous_digest, Some(c2s.digest()));
-        assert_eq!(c4s.sequence_number, 3);
-        assert_eq!(c4s.previous_digest, Some(c3s.digest()));
-        assert_eq!(c3t, vec![d(10), d(11), d(12)]);
-        assert_eq!(c4t, vec![d(13)]);
+    #[tokio::test]
+    async fn test_certified_checkpoint_is_not_overwritten_with_different_digest() {
+        let (committee, key_pairs) = Committee::new_simple_test_committee();
+        let checkpoint = |timestamp_ms| {
+            let contents =
+                CheckpointContents::new_with_digests_only_for_tests([ExecutionDigests::random()]);
+            certify_for_test(
+                &committee,
+                &key_pairs,
+                CheckpointSummary::new(
+                    0,
+                    0,
+                    1,
+                    &contents,
+                    None,
+                    GasCostSummary::default(),
+                    None,
+                    timestamp_ms,
+                ),
+            )
+        };
 
-        // Pending at index 3 had 3 transactions of 40K size, and we configured 100K max.
-        // Verify that we split into 2 checkpoints.
-        let (c5c, c5s) = result.recv().await.unwrap();
-        let c5t = c5c.iter().map(|d| d.transaction).collect::<Vec<_>>();
-        let (c6c, c6s) = result.recv().await.unwrap();
-        let c6t = c6c.iter().map(|d| d.transaction).collect::<Vec<_>>();
-        assert_eq!(c5s.sequence_number, 4);
-        assert_eq!(c5s.previous_digest, Some(c4s.digest()));
-        assert_eq!(c6s.sequence_number, 5);
-        assert_eq!(c6s.previous_digest, Some(c5s.digest()));
-        assert_eq!(c5t, vec![d(15), d(16)]);
-        assert_eq!(c6t, vec![d(17)]);
+        let ckpt_dir = tempfile::tempdir().unwrap();
+        let store = CheckpointStore::new(ckpt_dir.path());
+        let original = checkpoint(0);
+        store.insert_certified_checkpoint(&original).unwrap();
+        // Re-inserting the same checkpoint is allowed.
+        store.check_certified_checkpoint(&original).unwrap();
+        store.insert_certified_checkpoint(&original).unwrap();
+
+        let conflicting = checkpoint(1);
+        assert_eq!(
+            store.check_certified_checkpoint(&conflicting).unwrap_err(),
+            SuiError::ConflictingCertifiedCheckpoint {
+                sequence_number: 0,
+                existing: *original.digest(),
+                new: *conflicting.digest(),
+            }
+        );
+        assert_eq!(
+            store
+                .get_checkpoint_by_sequence_number(0)
+                .unwrap()
+                .unwrap()
+                .digest(),
+            original.digest()
+        );
+        assert!(store
+            .get_checkpoint_by_digest(conflicting.digest())
+            .unwrap()
+            .is_none());
+    }
 
-        let c1ss = SignedCheckpointSummary::new(c1s.epoch, c1s, state.secret.deref(), state.name);
-        let c2ss = SignedCheckpointSummary::new(c2s.epoch, c2s, state.secret.deref(), state.name);
+    #[tokio::test]
+    async fn test_checkpoint_summary_json() {
+        let (committee, key_pairs) = Committee::new_simple_test_committee();
+        let contents =
+            CheckpointContents::new_with_digests_only_for_tests([ExecutionDigests::random()]);
+        let checkpoint = certify_for_test(
+            &committee,
+            &key_pairs,
+            CheckpointSummary::new(
+                0,
+                0,
+                1,
+                &contents,
+                None,
+                GasCostSummary::new(11, 12, 11, 1),
+                None,
+                100,
+            ),
+        );
 
-        checkpoint_service
-            .notify_checkpoint_signature(
-                &epoch_store,
-                &CheckpointSignatureMessage { summary: c2ss },
+        let ckpt_dir = tempfile::tempdir().unwrap();
+        let store = CheckpointStore::new(ckpt_dir.path());
+        assert!(store.get_checkpoint_summary_json(0).unwrap().is_none());
+        store.insert_certified_checkpoint(&checkpoint).unwrap();
+
+        let json = store.get_checkpoint_summary_json(0).unwrap().unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(
+            value["content_digest"],
+            serde_json::Value::String(contents.digest().to_string())
+        );
+        assert_eq!(
+            value["epoch_rolling_gas_cost_summary"]["computationCost"],
+            serde_json::Value::String("11".to_string())
+        );
+        let summary: CheckpointSummary = serde_json::from_str(&json).unwrap();
+        assert_eq!(&summary, checkpoint.data());
+    }
+
+    #[test]
+    fn test_write_fork_dump_retains_latest() {
+        let dir = tempfile::tempdir().unwrap();
+        let start = Utc::now();
+        let mut paths = vec![];
+        for seq in 0..5 {
+            let time = start + chrono::Duration::seconds(seq as i64);
+            paths.push(write_fork_dump(dir.path(), seq, &time, "dump", 3).unwrap());
+        }
+        let mut retained = std::fs::read_dir(dir.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .collect::<Vec<_>>();
+        retained.sort();
+        assert_eq!(retained, paths[2..]);
+        assert_eq!(std::fs::read_to_string(&paths[4]).unwrap(), "dump");
+    }
+
+    #[test]
+    fn test_build_fork_report() {
+        let shared = ExecutionDigests::random();
+        let local_contents = CheckpointContents::new_with_digests_only_for_tests([
+            shared,
+            ExecutionDigests::random(),
+        ]);
+        let other_contents = CheckpointContents::new_with_digests_only_for_tests([
+            shared,
+            ExecutionDigests::random(),
+        ]);
+        let summary = |contents: &CheckpointContents| {
+            CheckpointSummary::new(
+                0,
+                0,
+                2,
+                contents,
+                None,
+                GasCostSummary::default(),
+                None,
+                100,
+            )
+        };
+        let local = summary(&local_contents);
+        let other = summary(&other_contents);
+
+        let report = build_fork_report(&local, &local_contents, &local, &local_contents);
+        assert!(!report.summary.differs());
+        assert!(!report.contents.differs());
+        assert!(report.transactions.patch().hunks().is_empty());
+        assert!(report.effects.patch().hunks().is_empty());
+
+        let report = build_fork_report(&local, &local_contents, &other, &other_contents);
+        assert!(report.summary.differs());
+        assert!(report.contents.differs());
+        assert_eq!(report.transactions.patch().hunks().len(), 1);
+        assert_eq!(report.effects.patch().hunks().len(), 1);
+        let text = report.text();
+        assert!(text.starts_with("Summary Diff: "));
+        assert!(text.contains("Effects Diff: "));
+    }
+
+    #[tokio::test]
+    async fn test_signature_aggregator_only_records_local_digest_signers() {
+        let state = TestAuthorityBuilder::new().build().await;
+        let (committee, key_pairs) = Committee::new_simple_test_committee();
+        let names: Vec<AuthorityName> = key_pairs.iter().map(|kp| kp.public().into()).collect();
+        let summary = |network_total_transactions| {
+            let contents =
+                CheckpointContents::new_with_digests_only_for_tests([ExecutionDigests::random()]);
+            CheckpointSummary::new(
+                0,
+                0,
+                network_total_transactions,
+                &contents,
+                None,
+                GasCostSummary::default(),
+                None,
+                100,
             )
+        };
+        let local = summary(1);
+        let conflicting = summary(2);
+        let signature = |epoch, summary: &CheckpointSummary, i: usize| CheckpointSignatureMessage {
+            summary: SignedCheckpointSummary::new(epoch, summary.clone(), &key_pairs[i], names[i]),
+        };
+
+        let ckpt_dir = tempfile::tempdir().unwrap();
+        let mut aggregator = CheckpointSignatureAggregator {
+            next_index: 0,
+            digest: local.digest(),
+            summary: local.clone(),
+            signatures_by_digest: MultiStakeAggregator::new(Arc::new(committee)),
+            tables: CheckpointStore::new(ckpt_dir.path()),
+            state,
+            metrics: CheckpointMetrics::new_for_tests(),
+            split_brain_halt_hook: SplitBrainHaltHook::default(),
+            split_brain_lookback: 0,
+            split_brain_peers_per_faction: 1,
+            max_retained_fork_dumps: 1,
+            split_brain_report: Arc::new(Mutex::new(None)),
+            started: Instant::now(),
+            signers: HashSet::new(),
+        };
+
+        assert!(aggregator.try_aggregate(signature(0, &local, 0)).is_err());
+        // A signature for the wrong epoch is rejected
+        assert!(aggregator.try_aggregate(signature(1, &local, 1)).is_err());
+        // A signature for a different checkpoint does not count as participation
+        assert!(aggregator
+            .try_aggregate(signature(0, &conflicting, 2))
+            .is_err());
+        assert!(aggregator.try_aggregate(signature(0, &local, 3)).is_err());
+        assert_eq!(aggregator.signers, HashSet::from([names[0], names[3]]));
+
+        assert!(aggregator.try_aggregate(signature(0, &local, 1)).is_ok());
+        assert_eq!(
+            aggregator.signers,
+            HashSet::from([names[0], names[1], names[3]])
+        );
+    }
+
+    #[test]
+    fn test_epoch_participation_fractions() {
+        let (committee, _) = Committee::new_simple_test_committee();
+        let names: Vec<_> = committee.names().copied().collect();
+        let mut participation = EpochParticipation::default();
+        assert!(participation
+            .fractions(&committee)
+            .values()
+            .all(|fraction| *fraction == 0.0));
+
+        participation.record(&names[..3].iter().copied().collect());
+        participation.record(&names[1..].iter().copied().collect());
+        let fractions = participation.fractions(&committee);
+        assert_eq!(fractions.len(), names.len());
+        assert_eq!(fractions[&names[0]], 0.5);
+        assert_eq!(fractions[&names[1]], 1.0);
+        assert_eq!(fractions[&names[names.len() - 1]], 0.5);
+    }
+
+    #[tokio::test]
+    async fn test_get_checkpoint_with_contents() {
+        let (committee, key_pairs) = Committee::new_simple_test_committee();
+        let contents =
+            CheckpointContents::new_with_digests_only_for_tests([ExecutionDigests::random()]);
+        let checkpoint = certify_for_test(
+            &committee,
+            &key_pairs,
+            CheckpointSummary::new(
+                0,
+                0,
+                1,
+                &contents,
+                None,
+                GasCostSummary::default(),
+                None,
+                100,
+            ),
+        );
+
+        let ckpt_dir = tempfile::tempdir().unwrap();
+        let store = CheckpointStore::new(ckpt_dir.path());
+        assert!(store.get_checkpoint_with_contents(0).unwrap().is_none());
+
+        store.insert_certified_checkpoint(&checkpoint).unwrap();
+        // The contents are not stored yet
+        assert!(store.get_checkpoint_with_contents(0).unwrap().is_none());
+
+        store
+            .checkpoint_content
+            .insert(contents.digest(), &contents)
             .unwrap();
-        checkpoint_service
-            .notify_checkpoint_signature(
-                &epoch_store,
-                &CheckpointSignatureMessage { summary: c1ss },
+        let (stored_checkpoint, stored_contents) =
+            store.get_checkpoint_with_contents(0).unwrap().unwrap();
+        assert_eq!(stored_checkpoint.digest(), checkpoint.digest());
+        assert_eq!(stored_contents, contents);
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "Local checkpoint fork detected")]
+    async fn test_injected_fork_is_detected() {
+        let (committee, key_pairs) = Committee::new_simple_test_committee();
+        let summary = |timestamp_ms| {
+            let contents =
+                CheckpointContents::new_with_digests_only_for_tests([ExecutionDigests::random()]);
+            CheckpointSummary::new(
+                0,
+                0,
+                1,
+                &contents,
+                None,
+                GasCostSummary::default(),
+                None,
+                timestamp_ms,
+            )
+        };
+
+        let ckpt_dir = tempfile::tempdir().unwrap();
+        let store = CheckpointStore::new(ckpt_dir.path());
+        store.inject_fork_for_test(0, &summary(0)).unwrap();
+        let certified = certify_for_test(&committee, &key_pairs, summary(1));
+        let _ = store.insert_certified_checkpoint(&certified);
+    }
+
+    #[tokio::test]
+    async fn test_prune_local_summaries_to_keeps_tail() {
+        let ckpt_dir = tempfile::tempdir().unwrap();
+        let store = CheckpointStore::new(ckpt_dir.path());
+        for seq in 0..5 {
+            let contents =
+                CheckpointContents::new_with_digests_only_for_tests([ExecutionDigests::random()]);
+            let summary = CheckpointSummary::new(
+                0,
+                seq,
+                seq + 1,
+                &contents,
+                None,
+                GasCostSummary::default(),
+                None,
+                0,
+            );
+            store
+                .locally_computed_checkpoints
+                .insert(&seq, &summary)
+                .unwrap();
+        }
+
+        store.prune_local_summaries_to(3).unwrap();
+        for seq in 0..3 {
+            assert!(store
+                .get_locally_computed_checkpoint(seq)
+                .unwrap()
+                .is_none());
+        }
+        for seq in 3..5 {
+            assert!(store
+                .get_locally_computed_checkpoint(seq)
+                .unwrap()
+                .is_some());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_compressed_checkpoint_contents_round_trip() {
+        let (committee, key_pairs) = Committee::new_simple_test_committee();
+        let full_contents = FullCheckpointContents::new_with_causally_ordered_transactions(
+            (0..256).flat_map(|_| FullCheckpointContents::random_for_testing()),
+        );
+        let contents = full_contents.checkpoint_contents();
+        let checkpoint = certify_for_test(
+            &committee,
+            &key_pairs,
+            CheckpointSummary::new(
+                0,
+                0,
+                contents.size() as u64,
+                &contents,
+                None,
+                GasCostSummary::default(),
+                None,
+                0,
+            ),
+        );
+
+        let ckpt_dir = tempfile::tempdir().unwrap();
+        let store = CheckpointStore::new_with_compression(ckpt_dir.path(), 3);
+        store
+            .insert_verified_checkpoint_contents(
+                &checkpoint,
+                VerifiedCheckpointContents::new_unchecked(full_contents.clone()),
             )
             .unwrap();
 
-        let c1sc = certified_result.recv().await.unwrap();
-        let c2sc = certified_result.recv().await.unwrap();
-        assert_eq!(c1sc.sequence_number, 0);
-        assert_eq!(c2sc.sequence_number, 1);
+        assert_eq!(
+            store
+                .get_full_checkpoint_contents_by_sequence_number(0)
+                .unwrap(),
+            Some(full_contents)
+        );
+        assert_eq!(
+            store.get_checkpoint_contents(contents.digest()).unwrap(),
+            Some(contents)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_checkpoint_timestamp() {
+        let (committee, key_pairs) = Committee::new_simple_test_committee();
+        let contents =
+            CheckpointContents::new_with_digests_only_for_tests([ExecutionDigests::random()]);
+        let checkpoint = certify_for_test(
+            &committee,
+            &key_pairs,
+            CheckpointSummary::new(
+                0,
+                0,
+                1,
+                &contents,
+                None,
+                GasCostSummary::default(),
+                None,
+                1234,
+            ),
+        );
+
+        let ckpt_dir = tempfile::tempdir().unwrap();
+        let store = CheckpointStore::new(ckpt_dir.path());
+        assert_eq!(store.checkpoint_timestamp(0).unwrap(), None);
+        store.insert_certified_checkpoint(&checkpoint).unwrap();
+        assert_eq!(store.checkpoint_timestamp(0).unwrap(), Some(1234));
+        assert_eq!(store.checkpoint_timestamp(1).unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_highest_contiguous_executed_skips_pruned_checkpoints() {
+        let (committee, key_pairs) = Committee::new_simple_test_committee();
+        let ckpt_dir = tempfile::tempdir().unwrap();
+        let store = CheckpointStore::new(ckpt_dir.path());
+
+        let mut checkpoints = vec![];
+        let mut previous_digest = None;
+        for seq in 0..4 {
+            let contents =
+                CheckpointContents::new_with_digests_only_for_tests([ExecutionDigests::random()]);
+            let checkpoint = certify_for_test(
+                &committee,
+                &key_pairs,
+                CheckpointSummary::new(
+                    0,
+                    seq,
+                    seq + 1,
+                    &contents,
+                    previous_digest,
+                    GasCostSummary::default(),
+                    None,
+                    0,
+                ),
+            );
+            previous_digest = Some(*checkpoint.digest());
+            store.insert_verified_checkpoint(&checkpoint).unwrap();
+            store
+                .update_highest_executed_checkpoint(&checkpoint)
+                .unwrap();
+            checkpoints.push(checkpoint);
+        }
+        assert_eq!(store.highest_contiguous_executed().unwrap(), Some(3));
+
+        // Prune the first two checkpoints, which are then not gaps.
+        store
+            .update_highest_pruned_checkpoint(
+                &checkpoints[1],
+                PrunedWatermarkViolation::Error,
+                &CheckpointMetrics::new_for_tests(),
+            )
+            .unwrap();
+        store.certified_checkpoints.remove(&0).unwrap();
+        store.certified_checkpoints.remove(&1).unwrap();
+        assert_eq!(store.highest_contiguous_executed().unwrap(), Some(3));
+
+        store.certified_checkpoints.remove(&3).unwrap();
+        assert_eq!(store.highest_contiguous_executed().unwrap(), Some(2));
+    }
+
+    #[tokio::test]
+    async fn test_highest_checkpoint_with_contents_watermark() {
+        let (committee, key_pairs) = Committee::new_simple_test_committee();
+        let ckpt_dir = tempfile::tempdir().unwrap();
+        let store = CheckpointStore::new(ckpt_dir.path());
+        assert!(store
+            .get_highest_checkpoint_with_contents()
+            .unwrap()
+            .is_none());
+
+        let mut checkpoints = vec![];
+        let mut previous_digest = None;
+        for seq in 0..2 {
+            let full_contents = FullCheckpointContents::random_for_testing();
+            let contents = full_contents.checkpoint_contents();
+            let checkpoint = certify_for_test(
+                &committee,
+                &key_pairs,
+                CheckpointSummary::new(
+                    0,
+                    seq,
+                    seq + 1,
+                    &contents,
+                    previous_digest,
+                    GasCostSummary::default(),
+                    None,
+                    0,
+                ),
+            );
+            previous_digest = Some(*checkpoint.digest());
+            store.insert_verified_checkpoint(&checkpoint).unwrap();
+            checkpoints.push((checkpoint, full_contents));
+        }
+
+        // Contents may be fetched out of order, the watermark never goes back
+        for seq in [1, 0] {
+            let (checkpoint, full_contents) = &checkpoints[seq];
+            store
+                .insert_verified_checkpoint_contents(
+                    checkpoint,
+                    VerifiedCheckpointContents::new_unchecked(full_contents.clone()),
+                )
+                .unwrap();
+            assert_eq!(
+                store
+                    .get_highest_checkpoint_with_contents()
+                    .unwrap()
+                    .map(|checkpoint| *checkpoint.sequence_number()),
+                Some(1)
+            );
+        }
     }
 
     #[async_trait]
@@ -2137,6 +5548,67 @@ mod tests {
         }
     }
 
+    /// Checkpoint service spawned by `spawn_service_for_test`, along with the receivers of its
+    /// outputs and what must outlive it.
+    struct TestCheckpointService {
+        service: Arc<CheckpointService>,
+        epoch_store: Arc<AuthorityPerEpochStore>,
+        metrics: Arc<CheckpointMetrics>,
+        output: mpsc::Receiver<(CheckpointContents, CheckpointSummary)>,
+        _certified_output: mpsc::Receiver<CertifiedCheckpointSummary>,
+        _exit: watch::Sender<()>,
+        _ckpt_dir: tempfile::TempDir,
+    }
+
+    impl TestCheckpointService {
+        /// Asserts that the builder keeps failing to build the pending checkpoints instead of
+        /// outputting one. The builder retries every second, so a few retries happen meanwhile.
+        async fn assert_build_fails(&mut self) {
+            assert!(timeout(Duration::from_secs(5), self.output.recv())
+                .await
+                .is_err());
+            assert!(self.metrics.checkpoint_errors.get() > 0);
+        }
+    }
+
+    /// Spawns a checkpoint service reading the effects from `store` and writing to a fresh
+    /// checkpoint store, with at most 3 transactions and 100K bytes per checkpoint.
+    fn spawn_service_for_test(
+        state: &Arc<AuthorityState>,
+        store: HashMap<TransactionDigest, TransactionEffects>,
+        config: CheckpointServiceConfig,
+    ) -> TestCheckpointService {
+        let (output, output_rcv) = mpsc::channel::<(CheckpointContents, CheckpointSummary)>(10);
+        let (certified_output, certified_output_rcv) =
+            mpsc::channel::<CertifiedCheckpointSummary>(10);
+        let ckpt_dir = tempfile::tempdir().unwrap();
+        let metrics = CheckpointMetrics::new_for_tests();
+        let epoch_store = state.epoch_store_for_testing();
+        let (service, exit) = CheckpointService::spawn(
+            state.clone(),
+            CheckpointStore::new(ckpt_dir.path()),
+            epoch_store.clone(),
+            Box::new(store),
+            Arc::new(StateAccumulator::new(state.database.clone())),
+            Box::new(output),
+            Box::new(certified_output),
+            metrics.clone(),
+            3,
+            100_000,
+            config,
+        )
+        .unwrap();
+        TestCheckpointService {
+            service,
+            epoch_store,
+            metrics,
+            output: output_rcv,
+            _certified_output: certified_output_rcv,
+            _exit: exit,
+            _ckpt_dir: ckpt_dir,
+        }
+    }
+
     fn p(i: u64, t: Vec<u8>) -> PendingCheckpoint {
         PendingCheckpoint {
             roots: t.into_iter().map(d).collect(),
@@ -2166,6 +5638,29 @@ mod tests {
         effects
     }
 
+    fn certify_for_test(
+        committee: &Committee,
+        key_pairs: &[AuthorityKeyPair],
+        summary: CheckpointSummary,
+    ) -> VerifiedCheckpoint {
+        let signatures = key_pairs
+            .iter()
+            .map(|key_pair| {
+                SignedCheckpointSummary::new(
+                    summary.epoch,
+                    summary.clone(),
+                    key_pair,
+                    key_pair.public().into(),
+                )
+                .auth_sig()
+                .clone()
+            })
+            .collect();
+        VerifiedCheckpoint::new_unchecked(
+            CertifiedCheckpointSummary::new(summary, signatures, committee).unwrap(),
+        )
+    }
+
     fn commit_cert_for_test(
         store: &mut HashMap<TransactionDigest, TransactionEffects>,
         state: Arc<AuthorityState>,
@@ -24,6 +24,15 @@ pub struct EpochMetrics {
     /// Total amount of gas rewards (i.e. computation gas cost) in the epoch.
     pub epoch_total_gas_reward: IntGauge,
 
+    /// Total storage cost charged in the epoch.
+    pub epoch_total_storage_cost: IntGauge,
+
+    /// Total storage rebate refunded in the epoch.
+    pub epoch_total_storage_rebate: IntGauge,
+
+    /// Total non-refundable storage fee kept by the system in the epoch.
+    pub epoch_total_non_refundable_storage_fee: IntGauge,
+
     // An active validator reconfigures through the following steps:
     // 1. Halt validator (a.k.a. close epoch) and stop accepting user transaction certs.
     // 2. Finishes processing all pending certificates and then send EndOfPublish message.
@@ -113,6 +122,21 @@ impl EpochMetrics {
                 "Total amount of gas rewards (i.e. computation gas cost) in the epoch",
                 registry
             ).unwrap(),
+            epoch_total_storage_cost: register_int_gauge_with_registry!(
+                "epoch_total_storage_cost",
+                "Total storage cost charged in the epoch",
+                registry
+            ).unwrap(),
+            epoch_total_storage_rebate: register_int_gauge_with_registry!(
+                "epoch_total_storage_rebate",
+                "Total storage rebate refunded in the epoch",
+                registry
+            ).unwrap(),
+            epoch_total_non_refundable_storage_fee: register_int_gauge_with_registry!(
+                "epoch_total_non_refundable_storage_fee",
+                "Total non-refundable storage fee kept by the system in the epoch",
+                registry
+            ).unwrap(),
             epoch_pending_certs_processed_time_since_epoch_close_ms: register_int_gauge_with_registry!(
                 "epoch_pending_certs_processed_time_since_epoch_close_ms",
                 "Time interval from when epoch was closed to when all pending certificates are processed",
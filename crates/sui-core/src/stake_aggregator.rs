@@ -124,6 +124,16 @@ impl<const STRENGTH: bool> StakeAggregator<AuthoritySignInfo, STRENGTH> {
         envelope: Envelope<T, AuthoritySignInfo>,
     ) -> InsertResult<AuthorityQuorumSignInfo<STRENGTH>> {
         let (data, sig) = envelope.into_data_and_sig();
+        self.insert_signature(&data, sig)
+    }
+
+    /// Same as `insert`, for a signature over `data` that is not wrapped in an envelope, so that
+    /// many signatures over the same data can be inserted without copying it for each of them.
+    pub fn insert_signature<T: Message + Serialize>(
+        &mut self,
+        data: &T,
+        sig: AuthoritySignInfo,
+    ) -> InsertResult<AuthorityQuorumSignInfo<STRENGTH>> {
         if self.committee.epoch != sig.epoch {
             return InsertResult::Failed {
                 error: SuiError::WrongEpoch {
@@ -140,7 +150,7 @@ impl<const STRENGTH: bool> StakeAggregator<AuthoritySignInfo, STRENGTH> {
                 ) {
                     Ok(aggregated) => {
                         match aggregated.verify_secure(
-                            &data,
+                            data,
                             Intent::sui_app(T::SCOPE),
                             self.committee(),
                         ) {
@@ -160,7 +170,7 @@ impl<const STRENGTH: bool> StakeAggregator<AuthoritySignInfo, STRENGTH> {
                                 let mut bad_authorities = vec![];
                                 for (name, sig) in &self.data.clone() {
                                     if let Err(err) = sig.verify_secure(
-                                        &data,
+                                        data,
                                         Intent::sui_app(T::SCOPE),
                                         self.committee(),
                                     ) {
@@ -254,16 +264,28 @@ where
         &mut self,
         k: K,
         envelope: Envelope<V, AuthoritySignInfo>,
+    ) -> InsertResult<AuthorityQuorumSignInfo<STRENGTH>> {
+        let (data, sig) = envelope.into_data_and_sig();
+        self.insert_signature(k, &data, sig)
+    }
+
+    /// Same as `insert`, for a signature over `data` that is not wrapped in an envelope. `data`
+    /// is only copied the first time a signature is inserted for `k`.
+    pub fn insert_signature(
+        &mut self,
+        k: K,
+        data: &V,
+        sig: AuthoritySignInfo,
     ) -> InsertResult<AuthorityQuorumSignInfo<STRENGTH>> {
         if let Some(entry) = self.stake_maps.get_mut(&k) {
-            entry.1.insert(envelope)
+            entry.1.insert_signature(data, sig)
         } else {
             let mut new_entry = StakeAggregator::new(self.committee.clone());
-            let result = new_entry.insert(envelope.clone());
+            let result = new_entry.insert_signature(data, sig);
             if !matches!(result, InsertResult::Failed { .. }) {
                 // This is very important: ensure that if the insert fails, we don't even add the
                 // new entry to the map.
-                self.stake_maps.insert(k, (envelope.into_data(), new_entry));
+                self.stake_maps.insert(k, (data.clone(), new_entry));
             }
             result
         }
@@ -24,7 +24,7 @@ use sui_types::transaction::VerifiedTransaction;
 use typed_store::Map;
 
 use crate::authority::AuthorityStore;
-use crate::checkpoints::CheckpointStore;
+use crate::checkpoints::{CheckpointProvenance, CheckpointStore};
 use crate::epoch::committee_store::CommitteeStore;
 
 #[derive(Clone)]
@@ -35,6 +35,7 @@ pub struct RocksDbStore {
     // in memory checkpoint watermark sequence numbers
     highest_verified_checkpoint: Arc<Mutex<Option<u64>>>,
     highest_synced_checkpoint: Arc<Mutex<Option<u64>>>,
+    record_checkpoint_provenance: bool,
 }
 
 impl RocksDbStore {
@@ -49,9 +50,17 @@ impl RocksDbStore {
             checkpoint_store,
             highest_verified_checkpoint: Arc::new(Mutex::new(None)),
             highest_synced_checkpoint: Arc::new(Mutex::new(None)),
+            record_checkpoint_provenance: false,
         }
     }
 
+    /// Records in the checkpoint store that the checkpoints inserted through this store were
+    /// obtained through state sync, see `CheckpointStore::get_checkpoint_provenance`.
+    pub fn with_checkpoint_provenance(mut self) -> Self {
+        self.record_checkpoint_provenance = true;
+        self
+    }
+
     pub fn get_objects(&self, object_keys: &[ObjectKey]) -> Result<Vec<Option<Object>>, SuiError> {
         self.authority_store.multi_get_object_by_key(object_keys)
     }
@@ -168,6 +177,15 @@ impl ReadStore for RocksDbStore {
 
 impl WriteStore for RocksDbStore {
     fn insert_checkpoint(&self, checkpoint: &VerifiedCheckpoint) -> Result<(), Self::Error> {
+        // State sync only inserts checkpoints that were verified against the committee of their
+        // epoch, so a conflict with the stored checkpoints means the committee certified a fork,
+        // which is not something the node can recover from.
+        if let Err(e) = self.checkpoint_store.check_certified_checkpoint(checkpoint) {
+            panic!(
+                "Cannot insert synced checkpoint {}: {e}",
+                checkpoint.sequence_number()
+            );
+        }
         if let Some(EndOfEpochData {
             next_epoch_committee,
             ..
@@ -178,7 +196,13 @@ impl WriteStore for RocksDbStore {
             self.insert_committee(committee)?;
         }
 
-        self.checkpoint_store.insert_verified_checkpoint(checkpoint)
+        // The peer the checkpoint was synced from is not known at this layer.
+        self.checkpoint_store
+            .insert_verified_checkpoint_with_provenance(
+                checkpoint,
+                self.record_checkpoint_provenance
+                    .then_some(CheckpointProvenance::StateSync { from: None }),
+            )
     }
 
     fn update_highest_synced_checkpoint(
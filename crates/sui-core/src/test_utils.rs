@@ -51,6 +51,9 @@ use sui_types::{
 use tokio::time::timeout;
 use tracing::{info, warn};
 
+// The stake aggregator is internal to the crate, it is only exposed here for the benches.
+pub use crate::stake_aggregator::MultiStakeAggregator;
+
 const WAIT_FOR_TX_TIMEOUT: Duration = Duration::from_secs(15);
 
 pub async fn send_and_confirm_transaction(
@@ -3,7 +3,9 @@
 
 use crate::authority::test_authority_builder::TestAuthorityBuilder;
 use crate::authority::AuthorityState;
-use crate::checkpoints::{CheckpointMetrics, CheckpointService, CheckpointServiceNoop};
+use crate::checkpoints::{
+    CheckpointMetrics, CheckpointService, CheckpointServiceConfig, CheckpointServiceNoop,
+};
 use crate::consensus_handler::ConsensusHandlerInitializer;
 use crate::consensus_manager::narwhal_manager::{NarwhalConfiguration, NarwhalManager};
 use crate::consensus_manager::{ConsensusManagerMetrics, ConsensusManagerTrait};
@@ -85,7 +87,9 @@ pub fn checkpoint_service_for_testing(state: Arc<AuthorityState>) -> Arc<Checkpo
         CheckpointMetrics::new_for_tests(),
         3,
         100_000,
-    );
+        CheckpointServiceConfig::default(),
+    )
+    .unwrap();
     checkpoint_service
 }
 
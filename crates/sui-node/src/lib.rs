@@ -57,8 +57,8 @@ use sui_core::authority_aggregator::AuthorityAggregator;
 use sui_core::authority_server::{ValidatorService, ValidatorServiceMetrics};
 use sui_core::checkpoints::checkpoint_executor::{CheckpointExecutor, StopReason};
 use sui_core::checkpoints::{
-    CheckpointMetrics, CheckpointService, CheckpointStore, SendCheckpointToStateSync,
-    SubmitCheckpointToConsensus,
+    CheckpointMetrics, CheckpointService, CheckpointServiceConfig, CheckpointStore,
+    SendCheckpointToStateSync, SubmitCheckpointToConsensus,
 };
 use sui_core::consensus_adapter::{
     CheckConnection, ConnectionMonitorStatus, ConsensusAdapter, ConsensusAdapterMetrics,
@@ -501,6 +501,7 @@ impl SuiNode {
             genesis.checkpoint_contents().clone(),
             &epoch_store,
         );
+        checkpoint_store.insert_genesis_committee(&genesis_committee)?;
         let state_sync_store = RocksDbStore::new(
             store.clone(),
             committee_store.clone(),
@@ -1114,7 +1115,7 @@ impl SuiNode {
             state_sync_handle,
             accumulator,
             checkpoint_metrics.clone(),
-        );
+        )?;
 
         // create a new map that gets injected into both the consensus handler and the consensus adapter
         // the consensus handler will write values forwarded from consensus, and the consensus adapter
@@ -1190,7 +1191,7 @@ impl SuiNode {
         state_sync_handle: state_sync::Handle,
         accumulator: Arc<StateAccumulator>,
         checkpoint_metrics: Arc<CheckpointMetrics>,
-    ) -> (Arc<CheckpointService>, watch::Sender<()>) {
+    ) -> SuiResult<(Arc<CheckpointService>, watch::Sender<()>)> {
         let epoch_start_timestamp_ms = epoch_store.epoch_start_state().epoch_start_timestamp_ms();
         let epoch_duration_ms = epoch_store.epoch_start_state().epoch_duration_ms();
 
@@ -1226,6 +1227,7 @@ impl SuiNode {
             checkpoint_metrics,
             max_tx_per_checkpoint,
             max_checkpoint_size_bytes,
+            CheckpointServiceConfig::default(),
         )
     }
 
@@ -43,7 +43,7 @@ use sui_archival::{verify_archive_with_checksums, verify_archive_with_genesis_co
 use sui_config::node::ArchiveReaderConfig;
 use sui_core::authority::authority_store_tables::AuthorityPerpetualTables;
 use sui_core::authority::AuthorityStore;
-use sui_core::checkpoints::CheckpointStore;
+use sui_core::checkpoints::{CheckpointMetrics, CheckpointStore, PrunedWatermarkViolation};
 use sui_core::db_checkpoint_handler::SUCCESS_MARKER;
 use sui_core::epoch::committee_store::CommitteeStore;
 use sui_core::storage::RocksDbStore;
@@ -790,7 +790,11 @@ fn start_summary_sync(
         checkpoint_store.update_highest_verified_checkpoint(&checkpoint)?;
         checkpoint_store.update_highest_synced_checkpoint(&checkpoint)?;
         checkpoint_store.update_highest_executed_checkpoint(&checkpoint)?;
-        checkpoint_store.update_highest_pruned_checkpoint(&checkpoint)?;
+        checkpoint_store.update_highest_pruned_checkpoint(
+            &checkpoint,
+            PrunedWatermarkViolation::default(),
+            &CheckpointMetrics::new(&Registry::default()),
+        )?;
         Ok::<(), anyhow::Error>(())
     })
 }
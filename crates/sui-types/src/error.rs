@@ -5,7 +5,7 @@
 use crate::{
     base_types::*,
     committee::{Committee, EpochId, StakeUnit},
-    digests::CheckpointContentsDigest,
+    digests::{CheckpointContentsDigest, CheckpointDigest},
     execution_status::CommandArgumentError,
     messages_checkpoint::CheckpointSequenceNumber,
     object::Owner,
@@ -601,6 +601,16 @@ pub enum SuiError {
 
     #[error("Failed to get JWK")]
     JWKRetrievalError,
+
+    #[error("Could not find executed transaction {digest:?} while building checkpoint")]
+    CheckpointTransactionNotFound { digest: TransactionDigest },
+
+    #[error("Certified checkpoint {sequence_number} is already stored with digest {existing:?}, refusing to overwrite it with digest {new:?}")]
+    ConflictingCertifiedCheckpoint {
+        sequence_number: CheckpointSequenceNumber,
+        existing: CheckpointDigest,
+        new: CheckpointDigest,
+    },
 }
 
 #[repr(u64)]